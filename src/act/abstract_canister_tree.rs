@@ -5,10 +5,22 @@ use quote::quote;
 
 use crate::{
     act::{
-        candid_file_generation, float32, float64, random, vm_value_conversion, CandidTypes,
-        CanisterMethods, VmValueConversion,
+        candid_file_generation,
+        certification::{self, Certification},
+        compatibility::{self, Incompatibility, Interface},
+        did, dynamic_call, float32, float64,
+        metadata::{self, Metadata},
+        random::{self, Randomness},
+        target_version::TargetVersion,
+        vm_value_conversion, CandidTypes, CanisterMethods, VmValueConversion,
         {
-            node::{AsNode, CandidType, CanisterMethod, Context, GuardFunction},
+            node::{
+                candid::derives,
+                canister_method::{InitMethod, PostUpgradeMethod},
+                http::http_types,
+                transform_function, AsNode, CandidType, CanisterMethod, Context, GuardFunction,
+                TransformFunction,
+            },
             Declaration, Declare,
         },
     },
@@ -23,18 +35,44 @@ pub struct AbstractCanisterTree {
     pub canister_methods: CanisterMethods,
     pub candid_types: CandidTypes,
     pub guard_functions: Vec<GuardFunction>,
+    pub transform_functions: Vec<TransformFunction>,
     pub header: TokenStream,
     pub body: TokenStream,
     pub vm_value_conversion: VmValueConversion,
     pub keywords: Vec<String>,
+    /// The derives put on every generated record, variant, and tuple. `None`
+    /// uses [`derives::default_derives`].
+    pub default_derives: Option<Vec<TokenStream>>,
+    /// Outer attributes put on every generated record, variant, and tuple
+    pub default_attributes: Vec<TokenStream>,
+    /// Certified variables and HTTP responses. See [`Certification`].
+    pub certification: Option<Certification>,
+    /// Generates bridge functions for calls to canisters and methods that
+    /// are only known at runtime. See
+    /// [`dynamic_call::generate_dynamic_call_bridge`].
+    pub dynamic_calls: bool,
+    /// How the RNG behind `getrandom` is seeded. See [`Randomness`].
+    pub randomness: Randomness,
+    /// Custom sections embedded in the Wasm, including the canister's
+    /// candid. See [`Metadata`].
+    pub metadata: Metadata,
+    /// The ic-cdk release the generated code is written against. See
+    /// [`TargetVersion`].
+    pub target_version: TargetVersion,
 }
 
+#[derive(Debug)]
 pub enum Error {
     MultipleTypeDefinitions(String),
     MultipleGuardFunctionDefinitions(String),
     MultipleCanisterMethodDefinitions(String),
     TypeNotFound(String),
     GuardFunctionNotFound(String),
+    IncompatibleInitAndPostUpgradeParams(Incompatibility),
+    CertifiedUpdateMethodNotFound(String),
+    InvalidTransformFunctionSignature(String),
+    InvalidTypeName(String),
+    BoundedWaitCallNotSupported(String),
 }
 
 impl AbstractCanisterTree {
@@ -50,6 +88,14 @@ impl AbstractCanisterTree {
             )
             .chain(self.verify_guard_function_defs_are_unique().err())
             .chain(self.verify_canister_method_defs_are_unique().err())
+            .chain(self.verify_post_upgrade_params_accept_init_args().err())
+            .chain(
+                self.verify_certified_update_methods_have_corresponding_definitions()
+                    .err(),
+            )
+            .chain(self.verify_transform_function_signatures().err())
+            .chain(self.verify_type_names_are_candid_identifiers().err())
+            .chain(self.verify_bounded_wait_calls_are_supported().err())
             .flatten()
             .collect::<Vec<_>>();
 
@@ -59,21 +105,44 @@ impl AbstractCanisterTree {
 
         let header = &self.header;
 
-        let randomness_implementation = random::generate_randomness_implementation();
+        let randomness_implementation =
+            random::generate_randomness_implementation(&self.randomness, self.target_version);
 
+        let vm_value_error = vm_value_conversion::generate_vm_value_error();
         let try_into_vm_value_trait = vm_value_conversion::generate_try_into_vm_value();
         let try_into_vm_value_impls = &self.vm_value_conversion.try_into_vm_value_impls;
         let try_from_vm_value_trait = vm_value_conversion::generate_try_from_vm_value();
         let try_from_vm_value_impls = &self.vm_value_conversion.try_from_vm_value_impls;
+        let vm_adapter =
+            vm_value_conversion::generate_vm_adapter(self.vm_value_conversion.vm_adapter);
 
         let body = &self.body;
 
         let canister_method_decls = self.generate_declarations(self.collect_canister_methods());
         let candid_type_decls = self.generate_declarations(self.collect_candid_types());
         let guard_function_decls = self.generate_declarations(self.guard_functions.clone());
+        let transform_function_decls = self.generate_declarations(self.transform_functions.clone());
+        let http_outcall_binding = transform_function::generate_http_outcall_binding(
+            &self.transform_functions,
+            self.target_version,
+        );
+        let dynamic_call_bridge =
+            dynamic_call::generate_dynamic_call_bridge(self.dynamic_calls, self.target_version);
+
+        let did = self.generate_did();
 
         let candid_file_generation_code =
-            candid_file_generation::generate_candid_file_generation_code();
+            candid_file_generation::generate_candid_file_generation_code(&did);
+
+        let metadata_code = metadata::generate_metadata_code(
+            &self.metadata,
+            self.target_version,
+            &did,
+            &did::generate_init_args(&self.candid_types, &self.canister_methods),
+        );
+
+        let certification_code =
+            certification::generate_certification_code(&self.certification, self.target_version);
 
         let azle_float64 = float64::generate();
         let azle_float32 = float32::generate();
@@ -83,18 +152,26 @@ impl AbstractCanisterTree {
 
             #randomness_implementation
 
+            #vm_value_error
             #try_into_vm_value_trait
             #try_into_vm_value_impls
             #try_from_vm_value_trait
             #try_from_vm_value_impls
+            #vm_adapter
 
             #body
 
             #(#canister_method_decls)*
             #(#candid_type_decls)*
             #(#guard_function_decls)*
+            #(#transform_function_decls)*
+            #http_outcall_binding
+            #dynamic_call_bridge
 
             #candid_file_generation_code
+            #metadata_code
+
+            #certification_code
 
             #azle_float64
             #azle_float32
@@ -116,24 +193,57 @@ impl AbstractCanisterTree {
         Context {
             keyword_list: self.keywords.clone(),
             cdk_name: self.cdk_name.clone(),
+            default_derives: self
+                .default_derives
+                .clone()
+                .unwrap_or_else(derives::default_derives),
+            default_attributes: self.default_attributes.clone(),
+            certification: self.certification.clone(),
+            vm_adapter: self.vm_value_conversion.vm_adapter,
+            target_version: self.target_version,
         }
     }
 
+    /// Checks whether this canister can replace `old` without breaking its
+    /// existing clients. See [`compatibility::check_compatibility`].
+    pub fn check_compatibility(
+        &self,
+        old: &AbstractCanisterTree,
+    ) -> Result<(), Vec<Incompatibility>> {
+        compatibility::check_compatibility(old, self)
+    }
+
+    /// The canister's .did file, which `get_candid_pointer` returns and the
+    /// `candid:service` metadata embeds. See [`did::generate_did`].
+    pub fn generate_did(&self) -> String {
+        did::generate_did(&self.candid_types, &self.canister_methods, &self.metadata)
+    }
+
+    /// Checks whether this canister can replace the one with the interface
+    /// `old`, usually read from the deployed canister's .did with
+    /// [`Interface::from_did`]
+    pub fn check_interface_compatibility(
+        &self,
+        old: &Interface,
+    ) -> Result<(), Vec<Incompatibility>> {
+        compatibility::check_interface_compatibility(old, &Interface::from(self))
+    }
+
     fn collect_canister_methods(&self) -> Vec<CanisterMethod> {
         let init_method = match &self.canister_methods.init_method {
             Some(init_method) => Some(CanisterMethod::Init(init_method.clone())),
-            None => None,
+            None => self
+                .needs_install_hooks()
+                .then(|| CanisterMethod::Init(InitMethod::default())),
         };
         let heartbeat_method = match &self.canister_methods.heartbeat_method {
             Some(heartbeat_method) => Some(CanisterMethod::Heartbeat(heartbeat_method.clone())),
             None => None,
         };
-        let inspect_message_method = match &self.canister_methods.inspect_message_method {
-            Some(inspect_message_method) => Some(CanisterMethod::InspectMessage(
-                inspect_message_method.clone(),
-            )),
-            None => None,
-        };
+        let inspect_message_method = self
+            .canister_methods
+            .generate_inspect_message_method(&self.metadata, self.target_version)
+            .map(CanisterMethod::InspectMessage);
         let pre_upgrade_method = match &self.canister_methods.pre_upgrade_method {
             Some(pre_upgrade_method) => {
                 Some(CanisterMethod::PreUpgrade(pre_upgrade_method.clone()))
@@ -144,14 +254,22 @@ impl AbstractCanisterTree {
             Some(post_upgrade_method) => {
                 Some(CanisterMethod::PostUpgrade(post_upgrade_method.clone()))
             }
-            None => None,
+            None => self
+                .needs_install_hooks()
+                .then(|| CanisterMethod::PostUpgrade(PostUpgradeMethod::default())),
         };
+        let http_handler = self
+            .canister_methods
+            .http_handler
+            .clone()
+            .map(CanisterMethod::Http);
         let system_canister_methods: Vec<_> = vec![
             init_method,
             heartbeat_method,
             inspect_message_method,
             pre_upgrade_method,
             post_upgrade_method,
+            http_handler,
         ]
         .iter()
         .filter_map(|system_canister_method| system_canister_method.clone())
@@ -173,6 +291,15 @@ impl AbstractCanisterTree {
         vec![system_canister_methods, query_methods, update_methods].concat()
     }
 
+    /// Seeding is started from init and post_upgrade, and the certified data
+    /// is set at their end, so they are generated even when the CDK doesn't
+    /// declare them
+    fn needs_install_hooks(&self) -> bool {
+        self.randomness.seed_on_install
+            || self.randomness.reseed_interval_seconds.is_some()
+            || self.certification.is_some()
+    }
+
     fn collect_candid_types(&self) -> Vec<CandidType> {
         let funcs: Vec<_> = self
             .candid_types
@@ -215,7 +342,11 @@ impl AbstractCanisterTree {
     }
 
     fn verify_type_defs_are_unique(&self) -> Result<(), Vec<Error>> {
-        let defined_names = self.candid_types.get_defined_names();
+        let http_type_names = match &self.canister_methods.http_handler {
+            Some(_) => http_types::http_types().get_defined_names(),
+            None => vec![],
+        };
+        let defined_names = [self.candid_types.get_defined_names(), http_type_names].concat();
         let duplicates = find_duplicates(&defined_names);
 
         match duplicates.is_empty() {
@@ -227,6 +358,56 @@ impl AbstractCanisterTree {
         }
     }
 
+    /// Type names are written unquoted in the .did file, where only
+    /// identifiers that aren't keywords are allowed
+    fn verify_type_names_are_candid_identifiers(&self) -> Result<(), Vec<Error>> {
+        let invalid_names: Vec<_> = self
+            .candid_types
+            .get_defined_names()
+            .into_iter()
+            .filter(|name| !did::is_candid_identifier(name))
+            .map(Error::InvalidTypeName)
+            .collect();
+
+        match invalid_names.is_empty() {
+            true => Ok(()),
+            false => Err(invalid_names),
+        }
+    }
+
+    fn verify_bounded_wait_calls_are_supported(&self) -> Result<(), Vec<Error>> {
+        if self.target_version != TargetVersion::IcCdk0_6 {
+            return Ok(());
+        }
+
+        let unsupported_calls: Vec<_> = self
+            .candid_types
+            .services
+            .iter()
+            .flat_map(|service| {
+                service.methods.iter().flat_map(move |method| {
+                    method
+                        .get_call_kinds(&service.call_kinds)
+                        .into_iter()
+                        .filter(|call_kind| call_kind.is_bounded_wait())
+                        .map(move |call_kind| {
+                            Error::BoundedWaitCallNotSupported(format!(
+                                "{}_{}_{}",
+                                call_kind.get_function_prefix(),
+                                service.name,
+                                method.name
+                            ))
+                        })
+                })
+            })
+            .collect();
+
+        match unsupported_calls.is_empty() {
+            true => Ok(()),
+            false => Err(unsupported_calls),
+        }
+    }
+
     fn verify_guard_function_defs_are_unique(&self) -> Result<(), Vec<Error>> {
         let defined_names = self.guard_functions.get_defined_names();
         let duplicates = find_duplicates(&defined_names);
@@ -241,7 +422,11 @@ impl AbstractCanisterTree {
     }
 
     fn verify_canister_method_defs_are_unique(&self) -> Result<(), Vec<Error>> {
-        let defined_names = self.canister_methods.get_defined_names();
+        let defined_names = [
+            self.canister_methods.get_defined_names(),
+            self.transform_functions.get_defined_names(),
+        ]
+        .concat();
         let duplicates = find_duplicates(&defined_names);
 
         match duplicates.is_empty() {
@@ -272,6 +457,88 @@ impl AbstractCanisterTree {
         }
     }
 
+    fn verify_post_upgrade_params_accept_init_args(&self) -> Result<(), Vec<Error>> {
+        let post_upgrade_method = match &self.canister_methods.post_upgrade_method {
+            Some(post_upgrade_method) => post_upgrade_method,
+            None => return Ok(()),
+        };
+        let init_params = match &self.canister_methods.init_method {
+            Some(init_method) => init_method.params.clone(),
+            None => vec![],
+        };
+
+        compatibility::check_post_upgrade_params(
+            &self.candid_types,
+            &init_params,
+            &post_upgrade_method.params,
+        )
+        .or_else(|incompatibilities| {
+            // The install arguments still decode when an opt changed
+            let errors: Vec<_> = incompatibilities
+                .into_iter()
+                .filter(|incompatibility| !incompatibility.is_warning())
+                .map(Error::IncompatibleInitAndPostUpgradeParams)
+                .collect();
+
+            if errors.is_empty() {
+                Ok(())
+            } else {
+                Err(errors)
+            }
+        })
+    }
+
+    fn verify_certified_update_methods_have_corresponding_definitions(
+        &self,
+    ) -> Result<(), Vec<Error>> {
+        let certified_update_methods = match &self.certification {
+            Some(certification) => &certification.certified_update_methods,
+            None => return Ok(()),
+        };
+        let update_method_names: HashSet<_> = self
+            .canister_methods
+            .update_methods
+            .iter()
+            .map(|update_method| &update_method.name)
+            .collect();
+
+        let missing: Vec<_> = certified_update_methods
+            .iter()
+            .filter(|name| !update_method_names.contains(name))
+            .collect();
+
+        match missing.is_empty() {
+            true => Ok(()),
+            false => Err(missing
+                .into_iter()
+                .map(|name| Error::CertifiedUpdateMethodNotFound(name.clone()))
+                .collect()),
+        }
+    }
+
+    fn verify_transform_function_signatures(&self) -> Result<(), Vec<Error>> {
+        let errors: Vec<_> = self
+            .transform_functions
+            .iter()
+            .filter_map(|transform_function| {
+                transform_function
+                    .verify_signature(&self.candid_types)
+                    .err()
+                    .map(|reason| {
+                        Error::InvalidTransformFunctionSignature(format!(
+                            "{}: {reason}",
+                            transform_function.name
+                        ))
+                    })
+            })
+            .collect();
+
+        match errors.is_empty() {
+            true => Ok(()),
+            false => Err(errors),
+        }
+    }
+
     fn verify_guard_function_names_have_corresponding_definitions(&self) -> Result<(), Vec<Error>> {
         let defined_names_set: HashSet<_> = self
             .guard_functions
@@ -305,6 +572,11 @@ impl HasTypeRefs for AbstractCanisterTree {
             .get_type_refs()
             .into_iter()
             .chain(self.candid_types.get_type_refs())
+            .chain(
+                self.transform_functions
+                    .iter()
+                    .flat_map(|transform_function| transform_function.get_type_refs()),
+            )
             .collect()
     }
 }
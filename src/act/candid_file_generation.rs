@@ -1,14 +1,16 @@
 use proc_macro2::TokenStream;
 use quote::quote;
 
-pub fn generate_candid_file_generation_code() -> TokenStream {
+/// Generates `get_candid_pointer`, which returns `did`, the .did file
+/// written by [did](crate::act::did). It is written from the ACT rather than
+/// exported from the canister methods, so it has the declared type names and
+/// docs and the declared reply types of methods that reply manually.
+pub fn generate_candid_file_generation_code(did: &str) -> TokenStream {
     quote! {
-        candid::export_service!();
-
         // Heavily inspired by https://stackoverflow.com/a/47676844
         #[no_mangle]
         pub fn get_candid_pointer() -> *mut std::os::raw::c_char {
-            let c_string = std::ffi::CString::new(__export_service()).unwrap();
+            let c_string = std::ffi::CString::new(#did).unwrap();
 
             c_string.into_raw()
         }
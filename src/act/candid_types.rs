@@ -12,6 +12,19 @@ pub struct CandidTypes {
     pub variants: Vec<Variant>,
 }
 
+impl CandidTypes {
+    /// Adds the types of another set, such as one of the prebuilt
+    /// [interfaces](crate::act::interfaces)
+    pub fn extend(&mut self, other: CandidTypes) {
+        self.funcs.extend(other.funcs);
+        self.records.extend(other.records);
+        self.services.extend(other.services);
+        self.tuples.extend(other.tuples);
+        self.type_aliases.extend(other.type_aliases);
+        self.variants.extend(other.variants);
+    }
+}
+
 impl HasTypeRefs for CandidTypes {
     fn get_type_refs(&self) -> Vec<TypeRef> {
         self.records
@@ -1,16 +1,21 @@
-use crate::traits::{HasDefinedNames, HasTypeRefs};
+use crate::{
+    act::{metadata::Metadata, target_version::TargetVersion},
+    traits::{HasDefinedNames, HasTypeRefs},
+};
 
 use super::node::{
     candid::TypeRef,
     canister_method::{
-        HeartbeatMethod, InitMethod, InspectMessageMethod, PostUpgradeMethod, PreUpgradeMethod,
-        QueryMethod, UpdateMethod,
+        inspect_message_policy, HeartbeatMethod, InitMethod, InspectMessageMethod,
+        PostUpgradeMethod, PreUpgradeMethod, QueryMethod, UpdateMethod,
     },
+    HttpHandler,
 };
 
 #[derive(Clone)]
 pub struct CanisterMethods {
     pub heartbeat_method: Option<HeartbeatMethod>,
+    pub http_handler: Option<HttpHandler>,
     pub init_method: Option<InitMethod>,
     pub inspect_message_method: Option<InspectMessageMethod>,
     pub post_upgrade_method: Option<PostUpgradeMethod>,
@@ -29,8 +34,66 @@ impl CanisterMethods {
                     .iter()
                     .filter_map(|m| m.guard_function_name.clone()),
             )
+            .chain(
+                self.query_methods
+                    .iter()
+                    .filter_map(|m| m.inspect_message_policy.as_ref())
+                    .chain(
+                        self.update_methods
+                            .iter()
+                            .filter_map(|m| m.inspect_message_policy.as_ref()),
+                    )
+                    .filter_map(|policy| policy.guard_function_name.clone()),
+            )
             .collect()
     }
+
+    /// The inspect_message to generate. If any query or update method has an
+    /// inspect message policy, the policies are applied first and the
+    /// inspect_message_method's body is only run for the methods without one.
+    /// The HTTP and metadata methods never have a policy.
+    pub fn generate_inspect_message_method(
+        &self,
+        metadata: &Metadata,
+        target_version: TargetVersion,
+    ) -> Option<InspectMessageMethod> {
+        let methods: Vec<_> = self
+            .query_methods
+            .iter()
+            .map(|m| (m.name.clone(), m.inspect_message_policy.clone()))
+            .chain(
+                self.update_methods
+                    .iter()
+                    .map(|m| (m.name.clone(), m.inspect_message_policy.clone())),
+            )
+            .chain(
+                self.http_handler
+                    .iter()
+                    .flat_map(|http_handler| http_handler.get_method_names())
+                    .map(|name| (name, None)),
+            )
+            .chain(
+                metadata
+                    .get_method_names()
+                    .into_iter()
+                    .map(|name| (name, None)),
+            )
+            .collect();
+
+        if methods.iter().all(|(_, policy)| policy.is_none()) {
+            return self.inspect_message_method.clone();
+        }
+
+        Some(InspectMessageMethod {
+            body: inspect_message_policy::generate_dispatcher_body(
+                &methods,
+                self.inspect_message_method
+                    .as_ref()
+                    .map(|inspect_message_method| &inspect_message_method.body),
+                target_version,
+            ),
+        })
+    }
 }
 
 impl HasDefinedNames for CanisterMethods {
@@ -39,6 +102,11 @@ impl HasDefinedNames for CanisterMethods {
             .iter()
             .map(|f| f.name.clone())
             .chain(self.update_methods.iter().map(|f| f.name.clone()))
+            .chain(
+                self.http_handler
+                    .iter()
+                    .flat_map(|http_handler| http_handler.get_method_names()),
+            )
             .collect()
     }
 }
@@ -0,0 +1,165 @@
+use proc_macro2::TokenStream;
+use quote::quote;
+
+use crate::act::target_version::TargetVersion;
+
+/// Certified variables for the generated canister. Values are stored in a
+/// Merkle tree whose root hash is set as the canister's certified data at
+/// the end of each certified update method, init and post_upgrade. Queries
+/// can then return a value together with the certificate and a witness for
+/// it.
+///
+/// The generated code provides
+/// - `_cdk_certify(key: &[u8], value: &[u8])` to add or replace a value
+/// - `_cdk_uncertify(key: &[u8])` to remove a value
+/// - `_cdk_certified_witness(key: &[u8]) -> Option<(Vec<u8>, Vec<u8>, Vec<u8>)>`
+///   to get the value, the certificate and the CBOR encoded witness for a
+///   key. It returns `None` for keys without a value, and outside of queries
+///   since certificates are only available there.
+///
+/// The tree lives on the heap, so it is empty after an upgrade. The CDK
+/// has to certify its values again in post_upgrade, after which the
+/// certified data is set from the tree.
+///
+/// The generated code depends on the `ic-certified-map`, `serde_cbor`, and
+/// `sha2` crates, and on `base64` when HTTP responses are certified.
+#[derive(Clone, Debug)]
+pub struct Certification {
+    /// The update methods after which the certified data is updated
+    pub certified_update_methods: Vec<String>,
+    /// Whether the generated `http_request` adds an `IC-Certificate` header
+    /// to its responses. Responses are certified by calling `_cdk_certify`
+    /// with the request path as the key and the response body as the value.
+    pub certify_http_responses: bool,
+}
+
+impl Certification {
+    pub fn is_certified_update_method(&self, name: &str) -> bool {
+        self.certified_update_methods
+            .iter()
+            .any(|certified_update_method| certified_update_method == name)
+    }
+
+    /// HTTP certification requires the tree to be labeled `http_assets`
+    fn get_tree_label(&self) -> &str {
+        if self.certify_http_responses {
+            "http_assets"
+        } else {
+            "certified_data"
+        }
+    }
+}
+
+/// Runs the body of a certified update method with a guard that updates the
+/// certified data when the method returns, whichever way it returns
+pub fn wrap_certified_update_body(body: &TokenStream) -> TokenStream {
+    quote! {
+        let _cdk_certified_data_guard = _CdkCertifiedDataGuard;
+        #body
+    }
+}
+
+/// The guard that init and post_upgrade start with, so that the certified
+/// data matches the tree once the values are certified again after an
+/// upgrade
+pub fn generate_install_hook(certification: &Option<Certification>) -> TokenStream {
+    match certification {
+        Some(_) => quote! {
+            let _cdk_certified_data_guard = _CdkCertifiedDataGuard;
+        },
+        None => quote!(),
+    }
+}
+
+pub fn generate_certification_code(
+    certification: &Option<Certification>,
+    target_version: TargetVersion,
+) -> TokenStream {
+    let certification = match certification {
+        Some(certification) => certification,
+        None => return quote!(),
+    };
+    let tree_label = proc_macro2::Literal::byte_string(certification.get_tree_label().as_bytes());
+    let set_certified_data = match target_version {
+        TargetVersion::IcCdk0_6 => quote!(ic_cdk::api::set_certified_data),
+        TargetVersion::IcCdk0_18 => quote!(ic_cdk::api::certified_data_set),
+    };
+
+    quote! {
+        thread_local! {
+            static _CDK_CERTIFIED_TREE: std::cell::RefCell<
+                ic_certified_map::RbTree<Vec<u8>, ic_certified_map::Hash>,
+            > = std::cell::RefCell::new(ic_certified_map::RbTree::new());
+            static _CDK_CERTIFIED_VALUES: std::cell::RefCell<
+                std::collections::BTreeMap<Vec<u8>, Vec<u8>>,
+            > = std::cell::RefCell::new(std::collections::BTreeMap::new());
+        }
+
+        const _CDK_CERTIFIED_TREE_LABEL: &[u8] = #tree_label;
+
+        fn _cdk_certify(key: &[u8], value: &[u8]) {
+            let hash: ic_certified_map::Hash = <sha2::Sha256 as sha2::Digest>::digest(value).into();
+            _CDK_CERTIFIED_TREE.with(|tree| tree.borrow_mut().insert(key.to_vec(), hash));
+            _CDK_CERTIFIED_VALUES.with(|values| values.borrow_mut().insert(key.to_vec(), value.to_vec()));
+        }
+
+        fn _cdk_uncertify(key: &[u8]) {
+            _CDK_CERTIFIED_TREE.with(|tree| tree.borrow_mut().delete(key));
+            _CDK_CERTIFIED_VALUES.with(|values| values.borrow_mut().remove(key));
+        }
+
+        fn _cdk_update_certified_data() {
+            _CDK_CERTIFIED_TREE.with(|tree| {
+                let root_hash = ic_certified_map::AsHashTree::root_hash(&*tree.borrow());
+                #set_certified_data(&ic_certified_map::labeled_hash(
+                    _CDK_CERTIFIED_TREE_LABEL,
+                    &root_hash,
+                ));
+            });
+        }
+
+        struct _CdkCertifiedDataGuard;
+
+        impl Drop for _CdkCertifiedDataGuard {
+            fn drop(&mut self) {
+                _cdk_update_certified_data();
+            }
+        }
+
+        fn _cdk_certified_witness(key: &[u8]) -> Option<(Vec<u8>, Vec<u8>, Vec<u8>)> {
+            let value = _CDK_CERTIFIED_VALUES.with(|values| values.borrow().get(key).cloned())?;
+            let certificate = ic_cdk::api::data_certificate()?;
+            let witness = _CDK_CERTIFIED_TREE.with(|tree| {
+                let witness = ic_certified_map::labeled(
+                    _CDK_CERTIFIED_TREE_LABEL,
+                    tree.borrow().witness(key),
+                );
+                let mut serializer = serde_cbor::ser::Serializer::new(vec![]);
+                serializer.self_describe().unwrap();
+                serde::Serialize::serialize(&witness, &mut serializer).unwrap();
+                serializer.into_inner()
+            });
+
+            Some((value, certificate, witness))
+        }
+    }
+}
+
+/// Generates `_cdk_http_certificate_header`, which builds the
+/// `IC-Certificate` header for a request path
+pub fn generate_http_certificate_header_function() -> TokenStream {
+    quote! {
+        fn _cdk_http_certificate_header(path: &str) -> Option<HeaderField> {
+            let (_, certificate, witness) = _cdk_certified_witness(path.as_bytes())?;
+
+            Some(HeaderField(
+                "IC-Certificate".to_string(),
+                format!(
+                    "certificate=:{}:, tree=:{}:",
+                    base64::encode(certificate),
+                    base64::encode(witness)
+                ),
+            ))
+        }
+    }
+}
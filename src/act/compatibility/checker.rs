@@ -0,0 +1,515 @@
+use std::collections::HashSet;
+use std::mem::discriminant;
+
+use super::{
+    interface::{to_candid_types, to_returns, Callable, Interface},
+    type_env::TypeEnv,
+    Incompatibility, Severity,
+};
+use crate::{
+    act::{
+        did::MAX_GENERIC_DEPTH,
+        node::{
+            candid::{service::Method, Func, Primitive, TypeRef},
+            node_parts::mode::Mode,
+            CandidType, Member, Param,
+        },
+        AbstractCanisterTree, CandidTypes,
+    },
+    label,
+};
+
+/// Reports every change from `old` to `new` that could break an existing
+/// client, following candid's subtyping rules: the interface of `new` must
+/// be a subtype of the interface of `old`. Params are contravariant, so a
+/// field added to an argument record must be opt. Return types are
+/// covariant, so a return value may not gain variant cases or lose fields
+/// that aren't opt. Removed methods, queries that became updates, and the
+/// params of init and post_upgrade are checked as well. An opt whose
+/// enclosed type changed incompatibly is reported as a [Severity::Warning],
+/// since candid decodes its values as null instead of failing.
+pub fn check_compatibility(
+    old: &AbstractCanisterTree,
+    new: &AbstractCanisterTree,
+) -> Result<(), Vec<Incompatibility>> {
+    check_interface_compatibility(&Interface::from(old), &Interface::from(new))
+}
+
+/// [check_compatibility] for interfaces that may come from a .did file,
+/// e.g. the one of the deployed canister from [Interface::from_did]
+pub fn check_interface_compatibility(
+    old: &Interface,
+    new: &Interface,
+) -> Result<(), Vec<Incompatibility>> {
+    let old_env = TypeEnv::new(&old.candid_types);
+    let new_env = TypeEnv::new(&new.candid_types);
+    let mut checker = Checker::new(&old_env, &new_env);
+
+    checker.check_sequence(
+        &old.init_params,
+        &new.init_params,
+        Variance::Contravariant,
+        &["init".to_string()],
+        "arg",
+    );
+
+    checker.check_sequence(
+        &old.post_upgrade_params,
+        &new.post_upgrade_params,
+        Variance::Contravariant,
+        &["post_upgrade".to_string()],
+        "arg",
+    );
+
+    for (name, old_method) in &old.methods {
+        let path = vec![format!("method {name}")];
+        match new.methods.iter().find(|(new_name, _)| new_name == name) {
+            Some((_, new_method)) => {
+                checker.check_callable(new_method, old_method, Variance::Covariant, &path)
+            }
+            None => checker.report(&path, "method was removed".to_string()),
+        }
+    }
+
+    checker.finish()
+}
+
+/// The IC passes the install arguments, which are encoded with the init
+/// params, to post_upgrade as well. Reports every way in which the
+/// post_upgrade params could fail to decode them.
+pub fn check_post_upgrade_params(
+    candid_types: &CandidTypes,
+    init_params: &[Param],
+    post_upgrade_params: &[Param],
+) -> Result<(), Vec<Incompatibility>> {
+    let env = TypeEnv::new(candid_types);
+    let mut checker = Checker::new(&env, &env);
+
+    checker.check_sequence(
+        &to_candid_types(init_params),
+        &to_candid_types(post_upgrade_params),
+        Variance::Contravariant,
+        &["post_upgrade".to_string()],
+        "arg",
+    );
+
+    checker.finish()
+}
+
+/// Which side of the upgrade the subtype in a check belongs to. The new
+/// interface is the subtype at the top level and the roles swap every time
+/// a check descends into params.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+enum Variance {
+    Covariant,
+    Contravariant,
+}
+
+impl Variance {
+    fn flip(self) -> Variance {
+        match self {
+            Variance::Covariant => Variance::Contravariant,
+            Variance::Contravariant => Variance::Covariant,
+        }
+    }
+}
+
+struct Checker<'a> {
+    old_env: &'a TypeEnv,
+    new_env: &'a TypeEnv,
+    /// Pairs of named types, with their type arguments, that are assumed to
+    /// be compatible while they are being checked, so that recursive types
+    /// terminate
+    assumed: HashSet<(String, String, Variance)>,
+    /// How many generic type refs the current check is nested in
+    generic_depth: usize,
+    incompatibilities: Vec<Incompatibility>,
+}
+
+impl<'a> Checker<'a> {
+    fn new(old_env: &'a TypeEnv, new_env: &'a TypeEnv) -> Checker<'a> {
+        Checker {
+            old_env,
+            new_env,
+            assumed: HashSet::new(),
+            generic_depth: 0,
+            incompatibilities: vec![],
+        }
+    }
+
+    fn finish(self) -> Result<(), Vec<Incompatibility>> {
+        if self.incompatibilities.is_empty() {
+            Ok(())
+        } else {
+            Err(self.incompatibilities)
+        }
+    }
+
+    fn report(&mut self, path: &[String], reason: String) {
+        self.incompatibilities.push(Incompatibility {
+            path: path.to_vec(),
+            reason,
+            severity: Severity::Error,
+        })
+    }
+
+    /// Returns the environments of the subtype and the supertype
+    fn envs(&self, variance: Variance) -> (&'a TypeEnv, &'a TypeEnv) {
+        match variance {
+            Variance::Covariant => (self.new_env, self.old_env),
+            Variance::Contravariant => (self.old_env, self.new_env),
+        }
+    }
+
+    fn check_type(
+        &mut self,
+        sub: &CandidType,
+        sup: &CandidType,
+        variance: Variance,
+        path: &[String],
+    ) {
+        if let (CandidType::TypeRef(sub_ref), CandidType::TypeRef(sup_ref)) = (sub, sup) {
+            let key = (to_key(sub_ref), to_key(sup_ref), variance);
+            if !self.assumed.insert(key) {
+                return;
+            }
+        }
+
+        let is_generic = [sub, sup].iter().any(|candid_type| {
+            matches!(candid_type, CandidType::TypeRef(type_ref) if !type_ref.type_arguments.is_empty())
+        });
+        if !is_generic {
+            return self.check_structure(sub, sup, variance, path);
+        }
+
+        // A generic type that instantiates itself with ever larger type
+        // arguments never repeats a pair, so it is only followed this deep
+        if self.generic_depth == MAX_GENERIC_DEPTH {
+            return;
+        }
+        self.generic_depth += 1;
+        self.check_structure(sub, sup, variance, path);
+        self.generic_depth -= 1;
+    }
+
+    fn check_structure(
+        &mut self,
+        sub: &CandidType,
+        sup: &CandidType,
+        variance: Variance,
+        path: &[String],
+    ) {
+        let (sub_env, sup_env) = self.envs(variance);
+        let sub = sub_env.resolve(sub);
+        let sup = sup_env.resolve(sup);
+
+        match (&sub, &sup) {
+            (_, CandidType::Primitive(Primitive::Reserved)) => (),
+            (CandidType::Primitive(Primitive::Empty), _) => (),
+            (CandidType::Opt(sub_opt), CandidType::Opt(sup_opt)) => {
+                let first_new = self.incompatibilities.len();
+                self.check_type(
+                    &sub_opt.enclosed_type,
+                    &sup_opt.enclosed_type,
+                    variance,
+                    &extend(path, "opt".to_string()),
+                );
+
+                // Candid decodes a value that doesn't fit an opt as null, so
+                // clients keep working but lose the value
+                for incompatibility in &mut self.incompatibilities[first_new..] {
+                    if !incompatibility.is_warning() {
+                        incompatibility.severity = Severity::Warning;
+                        incompatibility.reason += ", so the opt decodes as null";
+                    }
+                }
+            }
+            (_, CandidType::Opt(_)) => (),
+            (CandidType::Primitive(Primitive::Nat), CandidType::Primitive(Primitive::Int)) => (),
+            (CandidType::Primitive(sub_primitive), CandidType::Primitive(sup_primitive))
+                if discriminant(sub_primitive) == discriminant(sup_primitive) => {}
+            (CandidType::Array(sub_array), CandidType::Array(sup_array)) => self.check_type(
+                &sub_array.enclosed_type,
+                &sup_array.enclosed_type,
+                variance,
+                &extend(path, "vec".to_string()),
+            ),
+            (
+                CandidType::Record(_) | CandidType::Tuple(_),
+                CandidType::Record(_) | CandidType::Tuple(_),
+            ) => self.check_fields(&to_fields(&sub), &to_fields(&sup), variance, path),
+            (CandidType::Variant(sub_variant), CandidType::Variant(sup_variant)) => self
+                .check_cases(
+                    &to_labeled(&sub_variant.members),
+                    &to_labeled(&sup_variant.members),
+                    variance,
+                    path,
+                ),
+            (CandidType::Func(sub_func), CandidType::Func(sup_func)) => {
+                let to_callable = |func: &Func| Callable {
+                    mode: func.mode.clone(),
+                    params: func.params.clone(),
+                    returns: to_returns(&func.return_type),
+                };
+                self.check_callable(
+                    &to_callable(sub_func),
+                    &to_callable(sup_func),
+                    variance,
+                    path,
+                )
+            }
+            (CandidType::Service(sub_service), CandidType::Service(sup_service)) => {
+                for sup_method in &sup_service.methods {
+                    let method_path = extend(path, format!("method {}", sup_method.name));
+                    match sub_service
+                        .methods
+                        .iter()
+                        .find(|sub_method| sub_method.name == sup_method.name)
+                    {
+                        Some(sub_method) => {
+                            let to_callable = |method: &Method| Callable {
+                                mode: method.mode.clone(),
+                                params: to_candid_types(&method.params),
+                                returns: to_returns(&method.return_type),
+                            };
+                            self.check_callable(
+                                &to_callable(sub_method),
+                                &to_callable(sup_method),
+                                variance,
+                                &method_path,
+                            )
+                        }
+                        None => self.report(
+                            &method_path,
+                            match variance {
+                                Variance::Covariant => "method was removed".to_string(),
+                                Variance::Contravariant => "method was added".to_string(),
+                            },
+                        ),
+                    }
+                }
+            }
+            (CandidType::TypeParam(sub_param), CandidType::TypeParam(sup_param))
+                if sub_param.name == sup_param.name => {}
+            (CandidType::TypeRef(sub_ref), CandidType::TypeRef(sup_ref))
+                if sub_ref.name == sup_ref.name => {}
+            _ => {
+                let (old, new) = match variance {
+                    Variance::Covariant => (&sup, &sub),
+                    Variance::Contravariant => (&sub, &sup),
+                };
+                self.report(
+                    path,
+                    format!("type changed from {} to {}", describe(old), describe(new)),
+                )
+            }
+        }
+    }
+
+    fn check_callable(
+        &mut self,
+        sub: &Callable,
+        sup: &Callable,
+        variance: Variance,
+        path: &[String],
+    ) {
+        // Composite queries can't be called by other canisters, so a
+        // method can't become one, and they can't become updates either
+        let mode_is_compatible = match (&sub.mode, &sup.mode) {
+            (Mode::Update, Mode::Query | Mode::CompositeQuery) => false,
+            (Mode::CompositeQuery, Mode::Query | Mode::Update) => false,
+            (Mode::Oneway, Mode::Oneway) => true,
+            (Mode::Oneway, _) | (_, Mode::Oneway) => false,
+            _ => true,
+        };
+        if !mode_is_compatible {
+            let (old, new) = match variance {
+                Variance::Covariant => (&sup.mode, &sub.mode),
+                Variance::Contravariant => (&sub.mode, &sup.mode),
+            };
+            self.report(
+                path,
+                format!(
+                    "changed from {} to {}",
+                    describe_mode(old),
+                    describe_mode(new)
+                ),
+            );
+        }
+
+        self.check_sequence(&sup.params, &sub.params, variance.flip(), path, "arg");
+        self.check_sequence(&sub.returns, &sup.returns, variance, path, "return");
+    }
+
+    /// Checks an argument or return value sequence. Extra values in the
+    /// subtype are ignored, missing values are only allowed when the
+    /// supertype doesn't require them.
+    fn check_sequence(
+        &mut self,
+        sub: &[CandidType],
+        sup: &[CandidType],
+        variance: Variance,
+        path: &[String],
+        kind: &str,
+    ) {
+        let (_, sup_env) = self.envs(variance);
+
+        for (index, sup_type) in sup.iter().enumerate() {
+            let value_path = extend(path, format!("{kind} {index}"));
+            match sub.get(index) {
+                Some(sub_type) => self.check_type(sub_type, sup_type, variance, &value_path),
+                None if is_optional(sup_env, sup_type) => (),
+                None => self.report(&value_path, missing_reason(kind, variance)),
+            }
+        }
+    }
+
+    fn check_fields(
+        &mut self,
+        sub: &[(u32, String, CandidType)],
+        sup: &[(u32, String, CandidType)],
+        variance: Variance,
+        path: &[String],
+    ) {
+        let (_, sup_env) = self.envs(variance);
+
+        for (id, name, sup_type) in sup {
+            let field_path = extend(path, format!("field {name}"));
+            match sub.iter().find(|(sub_id, _, _)| sub_id == id) {
+                Some((_, _, sub_type)) => {
+                    self.check_type(sub_type, sup_type, variance, &field_path)
+                }
+                None if is_optional(sup_env, sup_type) => (),
+                None => self.report(&field_path, missing_reason("field", variance)),
+            }
+        }
+    }
+
+    fn check_cases(
+        &mut self,
+        sub: &[(u32, String, CandidType)],
+        sup: &[(u32, String, CandidType)],
+        variance: Variance,
+        path: &[String],
+    ) {
+        for (id, name, sub_type) in sub {
+            let case_path = extend(path, format!("case {name}"));
+            match sup.iter().find(|(sup_id, _, _)| sup_id == id) {
+                Some((_, _, sup_type)) => self.check_type(sub_type, sup_type, variance, &case_path),
+                None => self.report(
+                    &case_path,
+                    match variance {
+                        Variance::Covariant => "case was added".to_string(),
+                        Variance::Contravariant => "case was removed".to_string(),
+                    },
+                ),
+            }
+        }
+    }
+}
+
+/// The fields of a record or tuple keyed by their label id. Tuples are
+/// records with the labels 0, 1, 2, ... in candid.
+fn to_fields(candid_type: &CandidType) -> Vec<(u32, String, CandidType)> {
+    match candid_type {
+        CandidType::Record(record) => to_labeled(&record.members),
+        CandidType::Tuple(tuple) => tuple
+            .elems
+            .iter()
+            .enumerate()
+            .map(|(index, elem)| (index as u32, index.to_string(), elem.candid_type.clone()))
+            .collect(),
+        _ => vec![],
+    }
+}
+
+/// The name of a type ref and its type arguments, which are part of the key
+/// since `Foo<nat>` and `Foo<text>` are different types
+fn to_key(type_ref: &TypeRef) -> String {
+    if type_ref.type_arguments.is_empty() {
+        type_ref.name.clone()
+    } else {
+        format!("{}{:?}", type_ref.name, type_ref.type_arguments)
+    }
+}
+
+fn to_labeled(members: &[Member]) -> Vec<(u32, String, CandidType)> {
+    members
+        .iter()
+        .map(|member| {
+            let id = member
+                .label_id
+                .unwrap_or_else(|| label::hash_label(&member.name));
+            (id, member.name.clone(), member.candid_type.clone())
+        })
+        .collect()
+}
+
+fn is_optional(env: &TypeEnv, candid_type: &CandidType) -> bool {
+    matches!(
+        env.resolve(candid_type),
+        CandidType::Opt(_)
+            | CandidType::Primitive(Primitive::Null)
+            | CandidType::Primitive(Primitive::Reserved)
+    )
+}
+
+fn missing_reason(kind: &str, variance: Variance) -> String {
+    match variance {
+        Variance::Covariant => format!("{kind} was removed"),
+        Variance::Contravariant => format!("{kind} was added without being opt"),
+    }
+}
+
+fn extend(path: &[String], segment: String) -> Vec<String> {
+    path.iter()
+        .cloned()
+        .chain(std::iter::once(segment))
+        .collect()
+}
+
+fn describe_mode(mode: &Mode) -> &'static str {
+    match mode {
+        Mode::Query => "query",
+        Mode::CompositeQuery => "composite_query",
+        Mode::Update => "update",
+        Mode::Oneway => "oneway",
+    }
+}
+
+fn describe(candid_type: &CandidType) -> String {
+    match candid_type {
+        CandidType::Array(_) => "vec".to_string(),
+        CandidType::Func(_) => "func".to_string(),
+        CandidType::Opt(_) => "opt".to_string(),
+        CandidType::Record(_) | CandidType::Tuple(_) => "record".to_string(),
+        CandidType::Service(_) => "service".to_string(),
+        CandidType::TypeAlias(type_alias) => type_alias.name.clone(),
+        CandidType::TypeParam(type_param) => type_param.name.clone(),
+        CandidType::TypeRef(type_ref) => type_ref.name.clone(),
+        CandidType::Variant(_) => "variant".to_string(),
+        CandidType::Primitive(primitive) => match primitive {
+            Primitive::Bool => "bool",
+            Primitive::Blob => "blob",
+            Primitive::Empty => "empty",
+            Primitive::Float32 => "float32",
+            Primitive::Float64 => "float64",
+            Primitive::Int => "int",
+            Primitive::Int8 => "int8",
+            Primitive::Int16 => "int16",
+            Primitive::Int32 => "int32",
+            Primitive::Int64 => "int64",
+            Primitive::Nat => "nat",
+            Primitive::Nat8 => "nat8",
+            Primitive::Nat16 => "nat16",
+            Primitive::Nat32 => "nat32",
+            Primitive::Nat64 => "nat64",
+            Primitive::Null => "null",
+            Primitive::Principal => "principal",
+            Primitive::Reserved => "reserved",
+            Primitive::String => "text",
+            Primitive::Void => "void",
+        }
+        .to_string(),
+    }
+}
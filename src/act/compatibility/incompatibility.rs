@@ -0,0 +1,35 @@
+use std::fmt;
+
+/// A change that keeps a new interface from being a candid subtype of the old
+/// one. The path leads from the method to the changed part of its signature,
+/// e.g. `method transfer -> arg 0 -> field memo`.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Incompatibility {
+    pub path: Vec<String>,
+    pub reason: String,
+    pub severity: Severity,
+}
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Severity {
+    /// The change breaks existing clients
+    Error,
+    /// Existing clients keep working but lose data, e.g. an opt whose values
+    /// no longer fit its new type and decode as null
+    Warning,
+}
+
+impl Incompatibility {
+    pub fn is_warning(&self) -> bool {
+        self.severity == Severity::Warning
+    }
+}
+
+impl fmt::Display for Incompatibility {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if self.is_warning() {
+            write!(f, "warning: ")?;
+        }
+        write!(f, "{}: {}", self.path.join(" -> "), self.reason)
+    }
+}
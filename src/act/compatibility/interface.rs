@@ -0,0 +1,389 @@
+use candid_parser::{
+    candid::types::{FuncMode, Label},
+    types::{Binding, Dec, FuncType, IDLProg, IDLType, PrimType, TypeField},
+};
+
+use crate::act::{
+    interfaces::{builders, VmConversionHooks},
+    node::{
+        candid::{Primitive, Record},
+        http::http_types,
+        node_parts::mode::Mode,
+        CandidType, Member, Param, ReturnType,
+    },
+    AbstractCanisterTree, CandidTypes,
+};
+
+/// The parts of a canister's interface that its clients and its install
+/// arguments depend on. It comes either from an ACT or from a .did file,
+/// usually the one of the deployed canister since its ACT is long gone.
+pub struct Interface {
+    pub(super) candid_types: CandidTypes,
+    pub(super) init_params: Vec<CandidType>,
+    pub(super) post_upgrade_params: Vec<CandidType>,
+    pub(super) methods: Vec<(String, Callable)>,
+}
+
+pub(super) struct Callable {
+    pub(super) mode: Mode,
+    pub(super) params: Vec<CandidType>,
+    pub(super) returns: Vec<CandidType>,
+}
+
+impl From<&AbstractCanisterTree> for Interface {
+    fn from(tree: &AbstractCanisterTree) -> Interface {
+        let canister_methods = &tree.canister_methods;
+        let to_callable = |mode: Mode, params: &Vec<Param>, return_type: &ReturnType| Callable {
+            mode,
+            params: to_candid_types(params),
+            returns: to_returns(return_type),
+        };
+
+        let mut candid_types = tree.candid_types.clone();
+        if canister_methods.http_handler.is_some() {
+            candid_types.extend(http_types::http_types());
+        }
+
+        // The same methods as the .did file of [did](crate::act::did)
+        let http_methods = canister_methods
+            .http_handler
+            .iter()
+            .flat_map(|http_handler| http_handler.get_method_names())
+            .map(|name| {
+                let mode = if name == "http_request" {
+                    Mode::Query
+                } else {
+                    Mode::Update
+                };
+                let callable = Callable {
+                    mode,
+                    params: vec![builders::type_ref("HttpRequest")],
+                    returns: vec![builders::type_ref("HttpResponse")],
+                };
+
+                (name, callable)
+            });
+        let candid_interface_query = tree.metadata.candid_interface_query.then(|| {
+            (
+                "__get_candid_interface_tmp_hack".to_string(),
+                Callable {
+                    mode: Mode::Query,
+                    params: vec![],
+                    returns: vec![builders::primitive(Primitive::String)],
+                },
+            )
+        });
+
+        Interface {
+            candid_types,
+            init_params: canister_methods
+                .init_method
+                .as_ref()
+                .map(|init_method| to_candid_types(&init_method.params))
+                .unwrap_or_default(),
+            post_upgrade_params: canister_methods
+                .post_upgrade_method
+                .as_ref()
+                .map(|post_upgrade_method| to_candid_types(&post_upgrade_method.params))
+                .unwrap_or_default(),
+            methods: canister_methods
+                .query_methods
+                .iter()
+                .map(|query_method| {
+                    (
+                        query_method.name.clone(),
+                        to_callable(Mode::Query, &query_method.params, &query_method.return_type),
+                    )
+                })
+                .chain(canister_methods.update_methods.iter().map(|update_method| {
+                    (
+                        update_method.name.clone(),
+                        to_callable(
+                            Mode::Update,
+                            &update_method.params,
+                            &update_method.return_type,
+                        ),
+                    )
+                }))
+                .chain(http_methods)
+                .chain(candid_interface_query)
+                .collect(),
+        }
+    }
+}
+
+impl Interface {
+    /// An interface with the single method `name`, e.g. to check a method
+    /// against the signature the system calls it with
+    pub(crate) fn with_method(
+        candid_types: &CandidTypes,
+        name: &str,
+        mode: Mode,
+        params: Vec<CandidType>,
+        return_type: &ReturnType,
+    ) -> Interface {
+        Interface {
+            candid_types: candid_types.clone(),
+            init_params: vec![],
+            post_upgrade_params: vec![],
+            methods: vec![(
+                name.to_string(),
+                Callable {
+                    mode,
+                    params,
+                    returns: to_returns(return_type),
+                },
+            )],
+        }
+    }
+
+    /// Reads the interface from the text of a .did file. The IC passes the
+    /// install arguments to post_upgrade as well, so the service's init args
+    /// are also the post_upgrade params. Imports aren't followed.
+    pub fn from_did(did: &str) -> Result<Interface, String> {
+        let prog: IDLProg = did.parse().map_err(|err| format!("{}", err))?;
+        candid_parser::check_prog(&mut candid_parser::candid::TypeEnv::new(), &prog)
+            .map_err(|err| format!("{}", err))?;
+        let converter = DidConverter { decs: &prog.decs };
+
+        let mut candid_types = CandidTypes {
+            funcs: vec![],
+            records: vec![],
+            services: vec![],
+            tuples: vec![],
+            type_aliases: vec![],
+            variants: vec![],
+        };
+
+        for dec in &prog.decs {
+            match dec {
+                Dec::TypD(Binding { id, typ }) => {
+                    match converter.convert_type(typ)? {
+                        CandidType::Record(record) => candid_types.records.push(Record {
+                            name: Some(id.clone()),
+                            ..record
+                        }),
+                        CandidType::Variant(variant) => candid_types
+                            .variants
+                            .push(builders::variant(Some(id), variant.members)),
+                        CandidType::Func(func) => candid_types.funcs.push(builders::func(
+                            Some(id),
+                            func.params,
+                            (**func.return_type).clone(),
+                            func.mode,
+                            VmConversionHooks::none(),
+                        )),
+                        CandidType::Service(service) => candid_types.services.push(
+                            builders::service(id, service.methods, VmConversionHooks::none()),
+                        ),
+                        candid_type => candid_types
+                            .type_aliases
+                            .push(builders::type_alias(id, candid_type)),
+                    }
+                }
+                Dec::ImportType(path) | Dec::ImportServ(path) => {
+                    return Err(format!("Imports aren't supported, found import {}", path))
+                }
+            }
+        }
+
+        let (init_params, service) = match &prog.actor {
+            Some(IDLType::ClassT(args, service)) => (converter.convert_types(args)?, &**service),
+            Some(service) => (vec![], service),
+            None => return Err("The .did file has no service".to_string()),
+        };
+
+        Ok(Interface {
+            candid_types,
+            post_upgrade_params: init_params.clone(),
+            init_params,
+            methods: converter.convert_service_methods(service)?,
+        })
+    }
+}
+
+struct DidConverter<'a> {
+    decs: &'a [Dec],
+}
+
+impl<'a> DidConverter<'a> {
+    fn convert_types(&self, idl_types: &[IDLType]) -> Result<Vec<CandidType>, String> {
+        idl_types
+            .iter()
+            .map(|idl_type| self.convert_type(idl_type))
+            .collect()
+    }
+
+    fn convert_type(&self, idl_type: &IDLType) -> Result<CandidType, String> {
+        Ok(match idl_type {
+            IDLType::PrimT(prim_type) => builders::primitive(convert_primitive(prim_type)),
+            IDLType::PrincipalT => builders::primitive(Primitive::Principal),
+            IDLType::VarT(name) => builders::type_ref(name),
+            IDLType::OptT(enclosed_type) => builders::opt(self.convert_type(enclosed_type)?),
+            IDLType::VecT(enclosed_type) => builders::vec(self.convert_type(enclosed_type)?),
+            IDLType::RecordT(fields) => builders::inline_record(self.convert_fields(fields)?),
+            IDLType::VariantT(fields) => builders::inline_variant(self.convert_fields(fields)?),
+            IDLType::FuncT(func_type) => {
+                let callable = self.convert_func_type(func_type)?;
+
+                CandidType::Func(builders::func(
+                    None,
+                    callable.params,
+                    to_return_type(callable.returns)?,
+                    callable.mode,
+                    VmConversionHooks::none(),
+                ))
+            }
+            IDLType::ServT(_) => {
+                let methods = self
+                    .convert_service_methods(idl_type)?
+                    .into_iter()
+                    .map(|(name, callable)| {
+                        Ok(builders::method(
+                            &name,
+                            callable.mode,
+                            callable
+                                .params
+                                .into_iter()
+                                .enumerate()
+                                .map(|(index, candid_type)| {
+                                    builders::param(&format!("arg{}", index), candid_type)
+                                })
+                                .collect(),
+                            to_return_type(callable.returns)?,
+                        ))
+                    })
+                    .collect::<Result<_, String>>()?;
+
+                CandidType::Service(builders::service("", methods, VmConversionHooks::none()))
+            }
+            IDLType::ClassT(_, _) => {
+                return Err("Service constructors are only allowed as the main service".to_string())
+            }
+        })
+    }
+
+    fn convert_fields(&self, fields: &[TypeField]) -> Result<Vec<Member>, String> {
+        fields
+            .iter()
+            .map(|field| {
+                let candid_type = self.convert_type(&field.typ)?;
+
+                Ok(match &field.label {
+                    Label::Named(name) => builders::member(name, candid_type),
+                    Label::Id(id) | Label::Unnamed(id) => Member {
+                        label_id: Some(*id),
+                        ..builders::member(&id.to_string(), candid_type)
+                    },
+                })
+            })
+            .collect()
+    }
+
+    fn convert_func_type(&self, func_type: &FuncType) -> Result<Callable, String> {
+        let mode = match func_type.modes.first() {
+            Some(FuncMode::Query) => Mode::Query,
+            Some(FuncMode::CompositeQuery) => Mode::CompositeQuery,
+            Some(FuncMode::Oneway) => Mode::Oneway,
+            None => Mode::Update,
+        };
+
+        Ok(Callable {
+            mode,
+            params: self.convert_types(&func_type.args)?,
+            returns: self.convert_types(&func_type.rets)?,
+        })
+    }
+
+    /// The methods of a service type, which may be a reference to one
+    fn convert_service_methods(
+        &self,
+        service: &IDLType,
+    ) -> Result<Vec<(String, Callable)>, String> {
+        match self.resolve(service)? {
+            IDLType::ServT(bindings) => bindings
+                .iter()
+                .map(|binding| match self.resolve(&binding.typ)? {
+                    IDLType::FuncT(func_type) => {
+                        Ok((binding.id.clone(), self.convert_func_type(func_type)?))
+                    }
+                    _ => Err(format!("Method {} isn't a func", binding.id)),
+                })
+                .collect(),
+            _ => Err("The main service isn't a service".to_string()),
+        }
+    }
+
+    /// Follows references to named types until it reaches a structural type
+    fn resolve<'b>(&'b self, idl_type: &'b IDLType) -> Result<&'b IDLType, String> {
+        let mut resolved = idl_type;
+
+        for _ in 0..self.decs.len() + 1 {
+            match resolved {
+                IDLType::VarT(name) => {
+                    resolved = self
+                        .decs
+                        .iter()
+                        .find_map(|dec| match dec {
+                            Dec::TypD(Binding { id, typ }) if id == name => Some(typ),
+                            _ => None,
+                        })
+                        .ok_or_else(|| format!("Type {} isn't defined", name))?
+                }
+                _ => return Ok(resolved),
+            }
+        }
+
+        Err("A type is defined in terms of itself".to_string())
+    }
+}
+
+/// The ACT has a single return type, so funcs and service methods that
+/// return several values can't be represented
+fn to_return_type(returns: Vec<CandidType>) -> Result<CandidType, String> {
+    let mut returns = returns.into_iter();
+
+    match (returns.next(), returns.next()) {
+        (None, _) => Ok(builders::primitive(Primitive::Void)),
+        (Some(return_type), None) => Ok(return_type),
+        (Some(_), Some(_)) => {
+            Err("Funcs and service methods with several return values aren't supported".to_string())
+        }
+    }
+}
+
+fn convert_primitive(prim_type: &PrimType) -> Primitive {
+    match prim_type {
+        PrimType::Nat => Primitive::Nat,
+        PrimType::Nat8 => Primitive::Nat8,
+        PrimType::Nat16 => Primitive::Nat16,
+        PrimType::Nat32 => Primitive::Nat32,
+        PrimType::Nat64 => Primitive::Nat64,
+        PrimType::Int => Primitive::Int,
+        PrimType::Int8 => Primitive::Int8,
+        PrimType::Int16 => Primitive::Int16,
+        PrimType::Int32 => Primitive::Int32,
+        PrimType::Int64 => Primitive::Int64,
+        PrimType::Float32 => Primitive::Float32,
+        PrimType::Float64 => Primitive::Float64,
+        PrimType::Bool => Primitive::Bool,
+        PrimType::Text => Primitive::String,
+        PrimType::Null => Primitive::Null,
+        PrimType::Reserved => Primitive::Reserved,
+        PrimType::Empty => Primitive::Empty,
+    }
+}
+
+pub(super) fn to_candid_types(params: &[Param]) -> Vec<CandidType> {
+    params
+        .iter()
+        .map(|param| param.candid_type.clone())
+        .collect()
+}
+
+pub(super) fn to_returns(return_type: &ReturnType) -> Vec<CandidType> {
+    match &**return_type {
+        CandidType::Primitive(Primitive::Void) => vec![],
+        candid_type => vec![candid_type.clone()],
+    }
+}
@@ -0,0 +1,11 @@
+pub mod checker;
+pub mod incompatibility;
+pub mod interface;
+pub mod type_env;
+
+pub use checker::{check_compatibility, check_interface_compatibility, check_post_upgrade_params};
+pub use incompatibility::{Incompatibility, Severity};
+pub use interface::Interface;
+
+#[cfg(test)]
+mod tests;
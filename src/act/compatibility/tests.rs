@@ -0,0 +1,405 @@
+use quote::quote;
+
+use super::{check_compatibility, check_interface_compatibility, Interface, Severity};
+use crate::act::{
+    interfaces::{
+        builders::{member, primitive, record, type_ref},
+        icrc1, server_methods, VmConversionHooks,
+    },
+    metadata::Metadata,
+    node::{
+        candid::{type_param::TypeParams, Primitive, TypeArg, TypeParam},
+        node_parts::mode::Mode,
+        CandidType, ReturnType,
+    },
+    random::Randomness,
+    target_version::TargetVersion,
+    vm_value_conversion::VmValueConversion,
+    AbstractCanisterTree, CandidTypes, CanisterMethods,
+};
+
+fn check_dids(old: &str, new: &str) -> Result<(), Vec<String>> {
+    let old = Interface::from_did(old).unwrap();
+    let new = Interface::from_did(new).unwrap();
+
+    check_interface_compatibility(&old, &new).map_err(|incompatibilities| {
+        incompatibilities
+            .iter()
+            .map(|incompatibility| incompatibility.to_string())
+            .collect()
+    })
+}
+
+fn did_with_method(signature: &str) -> String {
+    format!("service : {{ f : {signature} }}")
+}
+
+fn check_methods(old: &str, new: &str) -> Result<(), Vec<String>> {
+    check_dids(&did_with_method(old), &did_with_method(new))
+}
+
+#[test]
+fn allows_identical_interfaces() {
+    let signature = "(record { a : nat; b : text }) -> (variant { ok : nat; err : text })";
+
+    assert_eq!(check_methods(signature, signature), Ok(()));
+}
+
+#[test]
+fn reports_required_fields_added_to_params() {
+    assert_eq!(
+        check_methods(
+            "(record { a : nat }) -> ()",
+            "(record { a : nat; b : nat }) -> ()"
+        ),
+        Err(vec![
+            "method f -> arg 0 -> field b: field was added without being opt".to_string()
+        ])
+    );
+    assert_eq!(
+        check_methods(
+            "(record { a : nat }) -> ()",
+            "(record { a : nat; b : opt nat }) -> ()"
+        ),
+        Ok(())
+    );
+}
+
+#[test]
+fn allows_fields_removed_from_params() {
+    assert_eq!(
+        check_methods(
+            "(record { a : nat; b : nat }) -> ()",
+            "(record { a : nat }) -> ()"
+        ),
+        Ok(())
+    );
+}
+
+#[test]
+fn reports_required_fields_removed_from_returns() {
+    assert_eq!(
+        check_methods(
+            "() -> (record { a : nat; b : nat })",
+            "() -> (record { a : nat })"
+        ),
+        Err(vec![
+            "method f -> return 0 -> field b: field was removed".to_string()
+        ])
+    );
+    assert_eq!(
+        check_methods(
+            "() -> (record { a : nat; b : opt nat })",
+            "() -> (record { a : nat })"
+        ),
+        Ok(())
+    );
+}
+
+#[test]
+fn allows_fields_added_to_returns() {
+    assert_eq!(
+        check_methods(
+            "() -> (record { a : nat })",
+            "() -> (record { a : nat; b : nat })"
+        ),
+        Ok(())
+    );
+}
+
+#[test]
+fn reports_cases_added_to_returns() {
+    assert_eq!(
+        check_methods("() -> (variant { a; b })", "() -> (variant { a; b; c })"),
+        Err(vec![
+            "method f -> return 0 -> case c: case was added".to_string()
+        ])
+    );
+    assert_eq!(
+        check_methods("() -> (variant { a; b })", "() -> (variant { a })"),
+        Ok(())
+    );
+}
+
+#[test]
+fn reports_cases_removed_from_params() {
+    assert_eq!(
+        check_methods("(variant { a; b }) -> ()", "(variant { a }) -> ()"),
+        Err(vec![
+            "method f -> arg 0 -> case b: case was removed".to_string()
+        ])
+    );
+    assert_eq!(
+        check_methods("(variant { a; b }) -> ()", "(variant { a; b; c }) -> ()"),
+        Ok(())
+    );
+}
+
+#[test]
+fn checks_params_and_returns_with_opposite_variance() {
+    // Every nat is an int, but not every int is a nat
+    assert_eq!(check_methods("() -> (int)", "() -> (nat)"), Ok(()));
+    assert_eq!(
+        check_methods("() -> (nat)", "() -> (int)"),
+        Err(vec![
+            "method f -> return 0: type changed from nat to int".to_string()
+        ])
+    );
+    assert_eq!(check_methods("(nat) -> ()", "(int) -> ()"), Ok(()));
+    assert_eq!(
+        check_methods("(int) -> ()", "(nat) -> ()"),
+        Err(vec![
+            "method f -> arg 0: type changed from int to nat".to_string()
+        ])
+    );
+}
+
+#[test]
+fn flips_the_variance_of_func_params() {
+    // The callback is called with the params of the method, so a case added
+    // to them breaks callbacks that were written for the old params
+    assert_eq!(
+        check_methods(
+            "(func (variant { a }) -> ()) -> ()",
+            "(func (variant { a; b }) -> ()) -> ()"
+        ),
+        Err(vec![
+            "method f -> arg 0 -> arg 0 -> case b: case was added".to_string()
+        ])
+    );
+    assert_eq!(
+        check_methods(
+            "(func (variant { a; b }) -> ()) -> ()",
+            "(func (variant { a }) -> ()) -> ()"
+        ),
+        Ok(())
+    );
+}
+
+#[test]
+fn reports_missing_args() {
+    assert_eq!(
+        check_methods("(nat) -> ()", "(nat, nat) -> ()"),
+        Err(vec![
+            "method f -> arg 1: arg was added without being opt".to_string()
+        ])
+    );
+    assert_eq!(check_methods("(nat) -> ()", "(nat, opt nat) -> ()"), Ok(()));
+}
+
+#[test]
+fn terminates_on_recursive_types() {
+    let tree = |leaf: &str| {
+        format!(
+            "type Tree = variant {{ leaf : {leaf}; node : record {{ Tree; Tree }} }};
+            service : {{ f : () -> (Tree) }}"
+        )
+    };
+
+    assert_eq!(check_dids(&tree("nat"), &tree("nat")), Ok(()));
+    assert_eq!(
+        check_dids(&tree("int"), &tree("nat")),
+        Ok(()),
+        "nat is a subtype of int at every depth"
+    );
+    assert_eq!(
+        check_dids(&tree("nat"), &tree("text")),
+        Err(vec![
+            "method f -> return 0 -> case leaf: type changed from nat to text".to_string()
+        ])
+    );
+}
+
+#[test]
+fn compares_numeric_labels_by_id() {
+    assert_eq!(
+        check_methods(
+            "() -> (record { 0 : nat; 42 : text })",
+            "() -> (record { 0 : nat; 42 : text; 43 : nat })"
+        ),
+        Ok(())
+    );
+    assert_eq!(
+        check_methods(
+            "() -> (record { 0 : nat; 42 : text })",
+            "() -> (record { 0 : nat; 43 : text })"
+        ),
+        Err(vec![
+            "method f -> return 0 -> field 42: field was removed".to_string()
+        ])
+    );
+}
+
+#[test]
+fn warns_when_the_enclosed_type_of_an_opt_changes() {
+    let old = Interface::from_did(&did_with_method("() -> (opt nat)")).unwrap();
+    let new = Interface::from_did(&did_with_method("() -> (opt text)")).unwrap();
+    let incompatibilities = check_interface_compatibility(&old, &new).unwrap_err();
+
+    assert_eq!(incompatibilities.len(), 1);
+    assert_eq!(incompatibilities[0].severity, Severity::Warning);
+    assert_eq!(
+        incompatibilities[0].to_string(),
+        "warning: method f -> return 0 -> opt: type changed from nat to text, so the opt decodes as null"
+    );
+    assert_eq!(check_methods("() -> (opt int)", "() -> (opt nat)"), Ok(()));
+}
+
+#[test]
+fn reports_mode_changes() {
+    assert_eq!(
+        check_methods("() -> () query", "() -> ()"),
+        Err(vec!["method f: changed from query to update".to_string()])
+    );
+    assert_eq!(check_methods("() -> ()", "() -> () query"), Ok(()));
+    assert_eq!(
+        check_methods("() -> () query", "() -> () composite_query"),
+        Err(vec![
+            "method f: changed from query to composite_query".to_string()
+        ])
+    );
+    assert_eq!(
+        check_methods("() -> () composite_query", "() -> ()"),
+        Err(vec![
+            "method f: changed from composite_query to update".to_string()
+        ])
+    );
+    assert_eq!(
+        check_methods("() -> () composite_query", "() -> () query"),
+        Ok(())
+    );
+}
+
+#[test]
+fn reports_removed_methods() {
+    assert_eq!(
+        check_dids(
+            "service : { f : () -> (); g : () -> () }",
+            "service : { f : () -> () }"
+        ),
+        Err(vec!["method g: method was removed".to_string()])
+    );
+}
+
+#[test]
+fn substitutes_type_arguments() {
+    let generic_box = CandidTypes {
+        records: vec![crate::act::node::candid::Record {
+            type_params: TypeParams(vec![TypeParam {
+                name: "T".to_string(),
+                try_into_vm_value_trait_bound: quote!(),
+                try_from_vm_value_trait_bound: |_| quote!(),
+            }]),
+            ..record(Some("Box"), vec![member("value", type_ref("T"))])
+        }],
+        ..empty_candid_types()
+    };
+    let returning_box = |type_arg: Primitive| {
+        let return_type = CandidType::TypeRef(crate::act::node::candid::TypeRef {
+            name: "Box".to_string(),
+            type_arguments: vec![TypeArg(primitive(type_arg))],
+        });
+
+        Interface::with_method(
+            &generic_box,
+            "f",
+            Mode::Query,
+            vec![],
+            &ReturnType::new(return_type),
+        )
+    };
+
+    assert!(check_interface_compatibility(
+        &returning_box(Primitive::Nat),
+        &returning_box(Primitive::Nat)
+    )
+    .is_ok());
+    assert_eq!(
+        check_interface_compatibility(
+            &returning_box(Primitive::Nat),
+            &returning_box(Primitive::String)
+        )
+        .unwrap_err()
+        .iter()
+        .map(|incompatibility| incompatibility.to_string())
+        .collect::<Vec<_>>(),
+        vec!["method f -> return 0 -> field value: type changed from nat to text".to_string()]
+    );
+}
+
+#[test]
+fn accepts_its_own_did() {
+    let icrc1 = icrc1(VmConversionHooks::none());
+    let (query_methods, update_methods) = server_methods(&icrc1.services[0], |_| quote!());
+    let tree = AbstractCanisterTree {
+        canister_methods: CanisterMethods {
+            query_methods,
+            update_methods,
+            ..empty_canister_methods()
+        },
+        candid_types: icrc1,
+        ..empty_tree()
+    };
+    let from_did = Interface::from_did(&tree.generate_did()).unwrap();
+
+    assert_eq!(
+        check_interface_compatibility(&from_did, &Interface::from(&tree)).map_err(
+            |incompatibilities| incompatibilities
+                .iter()
+                .map(|incompatibility| incompatibility.to_string())
+                .collect::<Vec<_>>()
+        ),
+        Ok(())
+    );
+    assert!(check_interface_compatibility(&Interface::from(&tree), &from_did).is_ok());
+    assert!(check_compatibility(&tree, &tree).is_ok());
+}
+
+fn empty_candid_types() -> CandidTypes {
+    CandidTypes {
+        funcs: vec![],
+        records: vec![],
+        services: vec![],
+        tuples: vec![],
+        type_aliases: vec![],
+        variants: vec![],
+    }
+}
+
+fn empty_canister_methods() -> CanisterMethods {
+    CanisterMethods {
+        heartbeat_method: None,
+        http_handler: None,
+        init_method: None,
+        inspect_message_method: None,
+        post_upgrade_method: None,
+        pre_upgrade_method: None,
+        query_methods: vec![],
+        update_methods: vec![],
+    }
+}
+
+fn empty_tree() -> AbstractCanisterTree {
+    AbstractCanisterTree {
+        cdk_name: "test".to_string(),
+        canister_methods: empty_canister_methods(),
+        candid_types: empty_candid_types(),
+        guard_functions: vec![],
+        transform_functions: vec![],
+        header: quote!(),
+        body: quote!(),
+        vm_value_conversion: VmValueConversion {
+            try_from_vm_value_impls: quote!(),
+            try_into_vm_value_impls: quote!(),
+            vm_adapter: false,
+        },
+        keywords: vec![],
+        default_derives: None,
+        default_attributes: vec![],
+        certification: None,
+        dynamic_calls: false,
+        target_version: TargetVersion::IcCdk0_6,
+        randomness: Randomness::default(),
+        metadata: Metadata::default(),
+    }
+}
@@ -0,0 +1,180 @@
+use std::collections::HashMap;
+
+use crate::act::{
+    node::{
+        candid::{Array, Opt, Primitive, TypeArg},
+        CandidType, ReturnType,
+    },
+    CandidTypes,
+};
+
+/// The named types of an interface so that type refs can be resolved
+pub struct TypeEnv(HashMap<String, CandidType>);
+
+impl TypeEnv {
+    pub fn new(candid_types: &CandidTypes) -> TypeEnv {
+        let named = |name: &Option<String>, candid_type: CandidType| {
+            name.clone().map(|name| (name, candid_type))
+        };
+
+        TypeEnv(
+            candid_types
+                .funcs
+                .iter()
+                .filter_map(|func| named(&func.name, CandidType::Func(func.clone())))
+                .chain(
+                    candid_types.records.iter().filter_map(|record| {
+                        named(&record.name, CandidType::Record(record.clone()))
+                    }),
+                )
+                .chain(
+                    candid_types
+                        .tuples
+                        .iter()
+                        .filter_map(|tuple| named(&tuple.name, CandidType::Tuple(tuple.clone()))),
+                )
+                .chain(candid_types.variants.iter().filter_map(|variant| {
+                    named(&variant.name, CandidType::Variant(variant.clone()))
+                }))
+                .chain(
+                    candid_types.services.iter().map(|service| {
+                        (service.name.clone(), CandidType::Service(service.clone()))
+                    }),
+                )
+                .chain(candid_types.type_aliases.iter().map(|type_alias| {
+                    (
+                        type_alias.name.clone(),
+                        CandidType::TypeAlias(type_alias.clone()),
+                    )
+                }))
+                .collect(),
+        )
+    }
+
+    /// The type defined under `name`
+    pub fn get(&self, name: &str) -> Option<&CandidType> {
+        self.0.get(name)
+    }
+
+    /// Follows type refs and type aliases until it reaches the type that
+    /// defines the structure. The type arguments of a type ref replace the
+    /// type params of its definition. Blobs are returned as `vec nat8` since
+    /// candid treats the two as the same type.
+    pub fn resolve(&self, candid_type: &CandidType) -> CandidType {
+        let mut resolved = candid_type.clone();
+
+        // A limit instead of a visited set since a well formed interface
+        // can't have an alias that refers back to itself
+        for _ in 0..self.0.len() + 1 {
+            resolved = match resolved {
+                CandidType::TypeRef(type_ref) => match self.0.get(&type_ref.name) {
+                    Some(candid_type) => {
+                        let type_args: HashMap<_, _> = get_type_param_names(candid_type)
+                            .into_iter()
+                            .zip(type_ref.type_arguments.iter().map(|type_arg| &type_arg.0))
+                            .collect();
+
+                        substitute(candid_type, &type_args)
+                    }
+                    None => return CandidType::TypeRef(type_ref),
+                },
+                CandidType::TypeAlias(type_alias) => *type_alias.aliased_type,
+                CandidType::Primitive(Primitive::Blob) => {
+                    return CandidType::Array(Array {
+                        enclosed_type: Box::new(CandidType::Primitive(Primitive::Nat8)),
+                    })
+                }
+                candid_type => return candid_type,
+            }
+        }
+
+        resolved
+    }
+}
+
+/// The names of the type params of a generic definition
+pub(crate) fn get_type_param_names(candid_type: &CandidType) -> Vec<String> {
+    let type_params = match candid_type {
+        CandidType::Record(record) => &record.type_params,
+        CandidType::Variant(variant) => &variant.type_params,
+        CandidType::Tuple(tuple) => &tuple.type_params,
+        CandidType::TypeAlias(type_alias) => &type_alias.type_params,
+        _ => return vec![],
+    };
+
+    type_params
+        .iter()
+        .map(|type_param| type_param.name.clone())
+        .collect()
+}
+
+/// Replaces the type params in `candid_type` with their type arguments. A
+/// type param may be referred to by a type ref with its name as well.
+fn substitute(candid_type: &CandidType, type_args: &HashMap<String, &CandidType>) -> CandidType {
+    if type_args.is_empty() {
+        return candid_type.clone();
+    }
+
+    let substitute = |candid_type: &CandidType| substitute(candid_type, type_args);
+
+    match candid_type {
+        CandidType::Array(array) => CandidType::Array(Array {
+            enclosed_type: Box::new(substitute(&array.enclosed_type)),
+        }),
+        CandidType::Func(func) => {
+            let mut func = func.clone();
+            func.params = func.params.iter().map(substitute).collect();
+            func.return_type = Box::new(ReturnType::new(substitute(&func.return_type)));
+            CandidType::Func(func)
+        }
+        CandidType::Opt(opt) => CandidType::Opt(Opt {
+            enclosed_type: Box::new(substitute(&opt.enclosed_type)),
+        }),
+        CandidType::Record(record) => {
+            let mut record = record.clone();
+            for member in &mut record.members {
+                member.candid_type = substitute(&member.candid_type);
+            }
+            CandidType::Record(record)
+        }
+        CandidType::Tuple(tuple) => {
+            let mut tuple = tuple.clone();
+            for elem in &mut tuple.elems {
+                elem.candid_type = substitute(&elem.candid_type);
+            }
+            CandidType::Tuple(tuple)
+        }
+        CandidType::TypeAlias(type_alias) => {
+            let mut type_alias = type_alias.clone();
+            type_alias.aliased_type = Box::new(substitute(&type_alias.aliased_type));
+            CandidType::TypeAlias(type_alias)
+        }
+        CandidType::TypeParam(type_param) => match type_args.get(&type_param.name) {
+            Some(type_arg) => (*type_arg).clone(),
+            None => candid_type.clone(),
+        },
+        CandidType::TypeRef(type_ref) if type_ref.type_arguments.is_empty() => {
+            match type_args.get(&type_ref.name) {
+                Some(type_arg) => (*type_arg).clone(),
+                None => candid_type.clone(),
+            }
+        }
+        CandidType::TypeRef(type_ref) => {
+            let mut type_ref = type_ref.clone();
+            type_ref.type_arguments = type_ref
+                .type_arguments
+                .iter()
+                .map(|type_arg| TypeArg(substitute(&type_arg.0)))
+                .collect();
+            CandidType::TypeRef(type_ref)
+        }
+        CandidType::Variant(variant) => {
+            let mut variant = variant.clone();
+            for member in &mut variant.members {
+                member.candid_type = substitute(&member.candid_type);
+            }
+            CandidType::Variant(variant)
+        }
+        CandidType::Primitive(_) | CandidType::Service(_) => candid_type.clone(),
+    }
+}
@@ -0,0 +1,548 @@
+use std::collections::HashMap;
+
+use crate::act::{
+    compatibility::type_env::{self, TypeEnv},
+    docs,
+    metadata::Metadata,
+    node::{
+        candid::{service::Method, Func, Primitive, Service},
+        http::http_types,
+        node_parts::mode::Mode,
+        CandidType, Member, Param, ReturnType,
+    },
+    CandidTypes, CanisterMethods,
+};
+
+const CANDID_KEYWORDS: [&str; 31] = [
+    "blob",
+    "bool",
+    "composite_query",
+    "empty",
+    "false",
+    "float32",
+    "float64",
+    "func",
+    "import",
+    "int",
+    "int8",
+    "int16",
+    "int32",
+    "int64",
+    "nat",
+    "nat8",
+    "nat16",
+    "nat32",
+    "nat64",
+    "null",
+    "oneway",
+    "opt",
+    "principal",
+    "query",
+    "record",
+    "reserved",
+    "service",
+    "text",
+    "true",
+    "type",
+    "variant",
+];
+
+/// Deeper type refs than this can only come from a generic type that
+/// instantiates itself with ever larger type arguments
+pub(crate) const MAX_GENERIC_DEPTH: usize = 32;
+
+/// Writes the canister's interface as a .did file without running the
+/// canister, unlike `candid::export_service!`. Generic types don't exist in
+/// Candid, so they are left out of the type definitions and written out in
+/// full wherever they are used. The HTTP gateway types and methods and the
+/// `__get_candid_interface_tmp_hack` query are included when the canister
+/// has them. Type names are written as they are, so they must be
+/// [Candid identifiers](is_candid_identifier).
+pub fn generate_did(
+    candid_types: &CandidTypes,
+    canister_methods: &CanisterMethods,
+    metadata: &Metadata,
+) -> String {
+    let http_types = canister_methods
+        .http_handler
+        .as_ref()
+        .map(|_| http_types::http_types());
+    let mut all_candid_types = candid_types.clone();
+    if let Some(http_types) = &http_types {
+        all_candid_types.extend(http_types.clone());
+    }
+    let writer = DidWriter::new(&all_candid_types);
+
+    let type_definitions: String = named_definitions(candid_types)
+        .into_iter()
+        .chain(http_types.iter().flat_map(named_definitions))
+        .map(|(name, candid_type, comment)| {
+            format!(
+                "{}type {} = {};\n",
+                comment.unwrap_or_default(),
+                name,
+                writer.write_definition(&candid_type)
+            )
+        })
+        .collect();
+
+    let methods: String = canister_methods
+        .query_methods
+        .iter()
+        .map(|query_method| (&query_method.definition, " query"))
+        .chain(
+            canister_methods
+                .update_methods
+                .iter()
+                .map(|update_method| (&update_method.definition, "")),
+        )
+        .map(|(definition, mode)| {
+            let comment = docs::to_candid_comment(&definition.docs, "  ");
+
+            format!(
+                "{}  {} : {} -> {}{};\n",
+                comment.unwrap_or_default(),
+                writer.write_label(&definition.name),
+                indent(&writer.write_documented_params(&definition.params)),
+                writer.write_return_type(&definition.return_type),
+                mode
+            )
+        })
+        .chain(
+            canister_methods
+                .http_handler
+                .iter()
+                .flat_map(|http_handler| http_handler.get_method_names())
+                .map(|name| {
+                    let mode = if name == "http_request" { " query" } else { "" };
+                    format!("  {} : (HttpRequest) -> (HttpResponse){};\n", name, mode)
+                }),
+        )
+        .chain(
+            metadata
+                .candid_interface_query
+                .then(|| "  __get_candid_interface_tmp_hack : () -> (text) query;\n".to_string()),
+        )
+        .collect();
+
+    let init_args = match &canister_methods.init_method {
+        Some(init_method) if !init_method.params.is_empty() => {
+            format!(
+                "{} -> ",
+                writer.write_documented_params(&init_method.params)
+            )
+        }
+        _ => "".to_string(),
+    };
+
+    format!("{type_definitions}service : {init_args}{{\n{methods}}}\n")
+}
+
+/// Writes the types of the init params, which is what tooling expects in the
+/// `candid:args` metadata
+pub fn generate_init_args(
+    candid_types: &CandidTypes,
+    canister_methods: &CanisterMethods,
+) -> String {
+    let init_params = match &canister_methods.init_method {
+        Some(init_method) => init_method.params.clone(),
+        None => vec![],
+    };
+
+    DidWriter::new(candid_types).write_params(&init_params)
+}
+
+/// The named types that can be written as a `type` definition, with the
+/// comment from their docs
+fn named_definitions(candid_types: &CandidTypes) -> Vec<(String, CandidType, Option<String>)> {
+    let funcs = candid_types.funcs.iter().filter_map(|func| {
+        func.name
+            .clone()
+            .map(|name| (name, CandidType::Func(func.clone()), None))
+    });
+    let records = candid_types
+        .records
+        .iter()
+        .filter(|record| record.type_params.is_empty())
+        .filter_map(|record| {
+            record.name.clone().map(|name| {
+                (
+                    name,
+                    CandidType::Record(record.clone()),
+                    docs::to_candid_comment(&record.docs, ""),
+                )
+            })
+        });
+    let variants = candid_types
+        .variants
+        .iter()
+        .filter(|variant| variant.type_params.is_empty())
+        .filter_map(|variant| {
+            variant.name.clone().map(|name| {
+                (
+                    name,
+                    CandidType::Variant(variant.clone()),
+                    docs::to_candid_comment(&variant.docs, ""),
+                )
+            })
+        });
+    let tuples = candid_types
+        .tuples
+        .iter()
+        .filter(|tuple| tuple.type_params.is_empty())
+        .filter_map(|tuple| {
+            tuple.name.clone().map(|name| {
+                (
+                    name,
+                    CandidType::Tuple(tuple.clone()),
+                    docs::to_candid_comment(&tuple.docs, ""),
+                )
+            })
+        });
+    let type_aliases = candid_types
+        .type_aliases
+        .iter()
+        .filter(|type_alias| type_alias.type_params.is_empty())
+        .map(|type_alias| {
+            (
+                type_alias.name.clone(),
+                *type_alias.aliased_type.clone(),
+                docs::to_candid_comment(&type_alias.docs, ""),
+            )
+        });
+    let services = candid_types.services.iter().map(|service| {
+        (
+            service.name.clone(),
+            CandidType::Service(service.clone()),
+            docs::to_candid_comment(&service.docs, ""),
+        )
+    });
+
+    funcs
+        .chain(records)
+        .chain(variants)
+        .chain(tuples)
+        .chain(type_aliases)
+        .chain(services)
+        .collect()
+}
+
+struct DidWriter {
+    type_env: TypeEnv,
+}
+
+impl DidWriter {
+    fn new(candid_types: &CandidTypes) -> DidWriter {
+        DidWriter {
+            type_env: TypeEnv::new(candid_types),
+        }
+    }
+
+    /// Writes the structure of a named type, which would otherwise be written
+    /// as its name
+    fn write_definition(&self, candid_type: &CandidType) -> String {
+        self.write_structure(candid_type, &HashMap::new(), 0)
+    }
+
+    fn write_type(
+        &self,
+        candid_type: &CandidType,
+        type_args: &HashMap<String, String>,
+        depth: usize,
+    ) -> String {
+        match candid_type {
+            CandidType::Func(Func {
+                name: Some(name), ..
+            }) if self.is_defined(name) => name.clone(),
+            CandidType::Record(record) => match &record.name {
+                Some(name) if self.is_defined(name) && record.type_params.is_empty() => {
+                    name.clone()
+                }
+                _ => self.write_structure(candid_type, type_args, depth),
+            },
+            CandidType::Variant(variant) => match &variant.name {
+                Some(name) if self.is_defined(name) && variant.type_params.is_empty() => {
+                    name.clone()
+                }
+                _ => self.write_structure(candid_type, type_args, depth),
+            },
+            CandidType::Tuple(tuple) => match &tuple.name {
+                Some(name) if self.is_defined(name) && tuple.type_params.is_empty() => name.clone(),
+                _ => self.write_structure(candid_type, type_args, depth),
+            },
+            CandidType::Service(service) if self.is_defined(&service.name) => service.name.clone(),
+            CandidType::TypeAlias(type_alias)
+                if self.is_defined(&type_alias.name) && type_alias.type_params.is_empty() =>
+            {
+                type_alias.name.clone()
+            }
+            CandidType::TypeAlias(type_alias) => {
+                self.write_type(&type_alias.aliased_type, type_args, depth)
+            }
+            CandidType::TypeParam(type_param) => match type_args.get(&type_param.name) {
+                Some(type_arg) => type_arg.clone(),
+                None => "reserved".to_string(),
+            },
+            CandidType::TypeRef(type_ref) => {
+                if let Some(type_arg) = type_args.get(&type_ref.name) {
+                    return type_arg.clone();
+                }
+
+                if type_ref.type_arguments.is_empty() {
+                    return type_ref.name.clone();
+                }
+
+                match self.type_env.get(&type_ref.name) {
+                    Some(generic_type) if depth < MAX_GENERIC_DEPTH => {
+                        let type_arg_values = type_ref
+                            .type_arguments
+                            .iter()
+                            .map(|type_arg| self.write_type(&type_arg.0, type_args, depth + 1));
+                        let generic_type_args = type_env::get_type_param_names(generic_type)
+                            .into_iter()
+                            .zip(type_arg_values)
+                            .collect();
+
+                        self.write_structure(generic_type, &generic_type_args, depth + 1)
+                    }
+                    _ => "reserved".to_string(),
+                }
+            }
+            _ => self.write_structure(candid_type, type_args, depth),
+        }
+    }
+
+    fn write_structure(
+        &self,
+        candid_type: &CandidType,
+        type_args: &HashMap<String, String>,
+        depth: usize,
+    ) -> String {
+        let write_type = |candid_type: &CandidType| self.write_type(candid_type, type_args, depth);
+
+        match candid_type {
+            CandidType::Array(array) => format!("vec {}", write_type(&array.enclosed_type)),
+            CandidType::Opt(opt) => format!("opt {}", write_type(&opt.enclosed_type)),
+            CandidType::Primitive(primitive) => write_primitive(primitive).to_string(),
+            CandidType::Record(record) => {
+                let fields: Vec<_> = record
+                    .members
+                    .iter()
+                    .map(|member| {
+                        let field = format!(
+                            "{} : {}",
+                            self.write_member_label(member),
+                            write_type(&member.candid_type)
+                        );
+
+                        (field, &member.docs)
+                    })
+                    .collect();
+
+                write_fields("record", fields)
+            }
+            CandidType::Variant(variant) => {
+                let cases: Vec<_> = variant
+                    .members
+                    .iter()
+                    .map(|member| {
+                        let case = match &member.candid_type {
+                            CandidType::Primitive(Primitive::Null) => {
+                                self.write_member_label(member)
+                            }
+                            candid_type => format!(
+                                "{} : {}",
+                                self.write_member_label(member),
+                                write_type(candid_type)
+                            ),
+                        };
+
+                        (case, &member.docs)
+                    })
+                    .collect();
+
+                write_fields("variant", cases)
+            }
+            CandidType::Tuple(tuple) => write_fields(
+                "record",
+                tuple
+                    .elems
+                    .iter()
+                    .map(|elem| (write_type(&elem.candid_type), &None))
+                    .collect(),
+            ),
+            CandidType::Func(func) => format!(
+                "func ({}) -> {}{}",
+                func.params
+                    .iter()
+                    .map(write_type)
+                    .collect::<Vec<_>>()
+                    .join(", "),
+                self.write_return_type(&func.return_type),
+                write_mode(&func.mode)
+            ),
+            CandidType::Service(service) => self.write_service(service),
+            CandidType::TypeAlias(type_alias) => write_type(&type_alias.aliased_type),
+            CandidType::TypeParam(_) | CandidType::TypeRef(_) => write_type(candid_type),
+        }
+    }
+
+    fn write_service(&self, service: &Service) -> String {
+        let methods: Vec<_> = service
+            .methods
+            .iter()
+            .map(|method| (self.write_service_method(method), &method.docs))
+            .collect();
+
+        write_fields("service", methods)
+    }
+
+    fn write_service_method(&self, method: &Method) -> String {
+        format!(
+            "{} : {} -> {}{}",
+            self.write_label(&method.name),
+            self.write_documented_params(&method.params),
+            self.write_return_type(&method.return_type),
+            write_mode(&method.mode)
+        )
+    }
+
+    fn write_params(&self, params: &[Param]) -> String {
+        let params: Vec<_> = params
+            .iter()
+            .map(|param| self.write_type(&param.candid_type, &HashMap::new(), 0))
+            .collect();
+
+        format!("({})", params.join(", "))
+    }
+
+    /// Writes params like [DidWriter::write_params], but with each param on
+    /// its own line below its docs when any of them has docs
+    fn write_documented_params(&self, params: &[Param]) -> String {
+        if params.iter().all(|param| param.docs.is_none()) {
+            return self.write_params(params);
+        }
+
+        let params: Vec<_> = params
+            .iter()
+            .map(|param| {
+                format!(
+                    "{}  {}",
+                    docs::to_candid_comment(&param.docs, "  ").unwrap_or_default(),
+                    indent(&self.write_type(&param.candid_type, &HashMap::new(), 0))
+                )
+            })
+            .collect();
+
+        format!("(\n{}\n)", params.join(",\n"))
+    }
+
+    fn write_return_type(&self, return_type: &ReturnType) -> String {
+        match &**return_type {
+            CandidType::Primitive(Primitive::Void) => "()".to_string(),
+            candid_type => format!("({})", self.write_type(candid_type, &HashMap::new(), 0)),
+        }
+    }
+
+    fn write_member_label(&self, member: &Member) -> String {
+        match member.label_id {
+            Some(label_id) => label_id.to_string(),
+            None => self.write_label(&member.name),
+        }
+    }
+
+    /// Labels that aren't Candid identifiers are quoted
+    fn write_label(&self, name: &str) -> String {
+        if is_candid_identifier(name) {
+            name.to_string()
+        } else {
+            format!("{:?}", name)
+        }
+    }
+
+    fn is_defined(&self, name: &str) -> bool {
+        self.type_env.get(name).is_some()
+    }
+}
+
+/// Whether `name` can be written unquoted in Candid. Type names can't be
+/// quoted, so they must all be identifiers.
+pub fn is_candid_identifier(name: &str) -> bool {
+    let mut chars = name.chars();
+    let is_identifier = matches!(chars.next(), Some(first) if first.is_ascii_alphabetic() || first == '_')
+        && chars.all(|c| c.is_ascii_alphanumeric() || c == '_');
+
+    is_identifier && !CANDID_KEYWORDS.contains(&name)
+}
+
+/// Writes the fields of a record or variant or the methods of a service.
+/// They go on one line unless one of them has docs, which are written as
+/// comments on the lines above it, or is itself written on several lines.
+fn write_fields(keyword: &str, fields: Vec<(String, &Option<String>)>) -> String {
+    if fields.is_empty() {
+        return format!("{keyword} {{}}");
+    }
+
+    let is_multiline = fields
+        .iter()
+        .any(|(field, docs)| docs.is_some() || field.contains('\n'));
+
+    if is_multiline {
+        let fields: String = fields
+            .iter()
+            .map(|(field, docs)| {
+                format!(
+                    "{}  {};\n",
+                    docs::to_candid_comment(docs, "  ").unwrap_or_default(),
+                    indent(field)
+                )
+            })
+            .collect();
+
+        format!("{keyword} {{\n{fields}}}")
+    } else {
+        let fields: Vec<_> = fields.into_iter().map(|(field, _)| field).collect();
+
+        format!("{keyword} {{ {}; }}", fields.join("; "))
+    }
+}
+
+/// Indents every line of `text` but the first, which continues the line it
+/// is written on
+fn indent(text: &str) -> String {
+    text.replace('\n', "\n  ")
+}
+
+fn write_mode(mode: &Mode) -> &'static str {
+    match mode {
+        Mode::Query => " query",
+        Mode::CompositeQuery => " composite_query",
+        Mode::Update => "",
+        Mode::Oneway => " oneway",
+    }
+}
+
+fn write_primitive(primitive: &Primitive) -> &'static str {
+    match primitive {
+        Primitive::Bool => "bool",
+        Primitive::Blob => "blob",
+        Primitive::Empty => "empty",
+        Primitive::Float32 => "float32",
+        Primitive::Float64 => "float64",
+        Primitive::Int => "int",
+        Primitive::Int8 => "int8",
+        Primitive::Int16 => "int16",
+        Primitive::Int32 => "int32",
+        Primitive::Int64 => "int64",
+        Primitive::Nat => "nat",
+        Primitive::Nat8 => "nat8",
+        Primitive::Nat16 => "nat16",
+        Primitive::Nat32 => "nat32",
+        Primitive::Nat64 => "nat64",
+        Primitive::Null => "null",
+        Primitive::Principal => "principal",
+        Primitive::Reserved => "reserved",
+        Primitive::String => "text",
+        Primitive::Void => "null",
+    }
+}
@@ -0,0 +1,60 @@
+use proc_macro2::TokenStream;
+use quote::quote;
+
+use crate::act::node::Param;
+
+/// Converts docs into one `#[doc = "..."]` attribute per line so that they
+/// show up as `///` comments in the generated lib.rs
+pub fn generate_doc_attributes(docs: &Option<String>) -> TokenStream {
+    match docs {
+        Some(docs) => {
+            let lines = docs.lines().map(|line| format!(" {line}"));
+            quote!(#(#[doc = #lines])*)
+        }
+        None => quote!(),
+    }
+}
+
+/// Rust doesn't allow doc comments on function parameters, so the docs of
+/// each param are appended to the docs of the function that declares it
+pub fn generate_callable_doc_attributes(docs: &Option<String>, params: &[Param]) -> TokenStream {
+    generate_doc_attributes(&combine_docs(
+        docs,
+        params.iter().map(|param| (&param.name, &param.docs)),
+        "# Params",
+    ))
+}
+
+/// Formats docs as Candid line comments to be placed on the lines above
+/// whatever they document in a generated .did file, like a type definition,
+/// a field, a param or a method
+pub fn to_candid_comment(docs: &Option<String>, indent: &str) -> Option<String> {
+    docs.as_ref().map(|docs| {
+        docs.lines()
+            .map(|line| {
+                if line.is_empty() {
+                    format!("{indent}//\n")
+                } else {
+                    format!("{indent}// {line}\n")
+                }
+            })
+            .collect()
+    })
+}
+
+fn combine_docs<'a>(
+    docs: &Option<String>,
+    child_docs: impl Iterator<Item = (&'a String, &'a Option<String>)>,
+    heading: &str,
+) -> Option<String> {
+    let child_docs: Vec<_> = child_docs
+        .filter_map(|(name, docs)| docs.as_ref().map(|docs| format!("- {name}: {docs}")))
+        .collect();
+
+    match (docs, child_docs.is_empty()) {
+        (None, true) => None,
+        (Some(docs), true) => Some(docs.clone()),
+        (None, false) => Some(format!("{heading}\n{}", child_docs.join("\n"))),
+        (Some(docs), false) => Some(format!("{docs}\n\n{heading}\n{}", child_docs.join("\n"))),
+    }
+}
@@ -0,0 +1,144 @@
+use proc_macro2::TokenStream;
+use quote::quote;
+
+use crate::act::target_version::{Payment, TargetVersion};
+
+/// Generates bridge functions for calling canisters and methods that are only
+/// known at runtime, with args and replies as `candid::IDLArgs`. The VM can
+/// build the args from its own values or parse them from candid text with
+/// `"(...)".parse::<candid::IDLArgs>()`.
+///
+/// The generated code provides
+/// - `_cdk_dynamic_call_signature(candid_service: &str, method: &str)` to get
+///   a method's signature from the candid text of a service
+/// - `_cdk_call_dynamic(canister_id, method, args, signature, cycles: u64)`
+/// - `_cdk_call_dynamic128(canister_id, method, args, signature, cycles: u128)`
+///
+/// Without a signature the args are encoded with the types inferred from
+/// their values and the reply is decoded without type annotations. With a
+/// signature the args and the reply are checked against and annotated with
+/// the method's types, so that for example a number is encoded as a `nat8`
+/// when the method expects one. With ic-cdk 0.6 encoding and decoding errors
+/// are returned as `CanisterError` rejections like the statically typed call
+/// functions do. ic-cdk 0.18's call errors can only be created by the system,
+/// so there every error is returned as a `String`, and `IDLArgs` comes from
+/// the `candid_parser` crate.
+pub fn generate_dynamic_call_bridge(
+    dynamic_calls: bool,
+    target_version: TargetVersion,
+) -> TokenStream {
+    if !dynamic_calls {
+        return quote!();
+    }
+
+    let candid_parser = target_version.generate_candid_parser_path();
+    let result_type = |ok_type: TokenStream| match target_version {
+        TargetVersion::IcCdk0_6 => quote!(ic_cdk::api::call::CallResult<#ok_type>),
+        TargetVersion::IcCdk0_18 => quote!(Result<#ok_type, String>),
+    };
+    let encoded_args_result_type = result_type(quote!(Vec<u8>));
+    let idl_args_result_type = result_type(quote!(#candid_parser::IDLArgs));
+    let (error, map_call_error) = match target_version {
+        TargetVersion::IcCdk0_6 => (
+            quote!((ic_cdk::api::call::RejectionCode::CanisterError, message)),
+            quote!(),
+        ),
+        TargetVersion::IcCdk0_18 => (quote!(message), quote!(.map_err(|err| err.to_string()))),
+    };
+    let call_raw = target_version.generate_call_raw(
+        quote!(canister_id_principal),
+        quote!(method),
+        quote!(args_raw),
+        &Payment::Cycles(quote!(cycles)),
+    );
+    let call_raw128 = target_version.generate_call_raw(
+        quote!(canister_id_principal),
+        quote!(method),
+        quote!(args_raw),
+        &Payment::Cycles128(quote!(cycles)),
+    );
+
+    quote! {
+        struct _CdkDynamicCallSignature {
+            env: candid::TypeEnv,
+            function: candid::types::Function,
+        }
+
+        fn _cdk_dynamic_call_signature(
+            candid_service: &str,
+            method: &str,
+        ) -> Result<_CdkDynamicCallSignature, String> {
+            let prog: #candid_parser::IDLProg = candid_service
+                .parse()
+                .map_err(|err: #candid_parser::Error| err.to_string())?;
+            let mut env = candid::TypeEnv::new();
+            let actor = #candid_parser::check_prog(&mut env, &prog)
+                .map_err(|err| err.to_string())?
+                .ok_or_else(|| "The candid does not define a service".to_string())?;
+            let function = env
+                .get_method(&actor, method)
+                .map_err(|err| err.to_string())?
+                .clone();
+
+            Ok(_CdkDynamicCallSignature { env, function })
+        }
+
+        fn _cdk_encode_dynamic_args(
+            args: &#candid_parser::IDLArgs,
+            signature: Option<&_CdkDynamicCallSignature>,
+        ) -> #encoded_args_result_type {
+            match signature {
+                Some(signature) => args.to_bytes_with_types(&signature.env, &signature.function.args),
+                None => args.to_bytes(),
+            }
+            .map_err(|err| {
+                let message = format!("failed to encode args: {}", err);
+                #error
+            })
+        }
+
+        fn _cdk_decode_dynamic_reply(
+            bytes: &[u8],
+            signature: Option<&_CdkDynamicCallSignature>,
+        ) -> #idl_args_result_type {
+            match signature {
+                Some(signature) => #candid_parser::IDLArgs::from_bytes_with_types(
+                    bytes,
+                    &signature.env,
+                    &signature.function.rets,
+                ),
+                None => #candid_parser::IDLArgs::from_bytes(bytes),
+            }
+            .map_err(|err| {
+                let message = format!("failed to decode reply: {}", err);
+                #error
+            })
+        }
+
+        async fn _cdk_call_dynamic(
+            canister_id_principal: candid::Principal,
+            method: &str,
+            args: #candid_parser::IDLArgs,
+            signature: Option<&_CdkDynamicCallSignature>,
+            cycles: u64,
+        ) -> #idl_args_result_type {
+            let args_raw = _cdk_encode_dynamic_args(&args, signature)?;
+            let reply = #call_raw #map_call_error?;
+
+            _cdk_decode_dynamic_reply(&reply, signature)
+        }
+
+        async fn _cdk_call_dynamic128(
+            canister_id_principal: candid::Principal,
+            method: &str,
+            args: #candid_parser::IDLArgs,
+            signature: Option<&_CdkDynamicCallSignature>,
+            cycles: u128,
+        ) -> #idl_args_result_type {
+            let args_raw = _cdk_encode_dynamic_args(&args, signature)?;
+            let reply = #call_raw128 #map_call_error?;
+
+            _cdk_decode_dynamic_reply(&reply, signature)
+        }
+    }
+}
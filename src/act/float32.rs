@@ -1,20 +1,75 @@
 use proc_macro2::TokenStream;
 use quote::quote;
 
+/// Generates the type used for candid float32 values. Its `Eq`, `Ord`, and
+/// `Hash` are all based on `f32::total_cmp` with every NaN canonicalized to
+/// `f32::NAN`, so they agree with each other and are safe to use in sorting,
+/// `BTreeMap`s, and `HashMap`s. This means that NaN equals NaN and that -0.0
+/// doesn't equal 0.0. It is encoded to and decoded from candid as a plain
+/// float32.
 pub fn generate() -> TokenStream {
     quote! {
-        #[derive(serde::Deserialize, Clone, Debug, candid::CandidType)]
+        #[derive(Clone, Copy, Debug, candid::CandidType)]
         struct _CdkFloat32(f32);
 
+        impl _CdkFloat32 {
+            fn canonicalize(value: f32) -> f32 {
+                if value.is_nan() {
+                    f32::NAN
+                } else {
+                    value
+                }
+            }
+
+            fn canonical(&self) -> f32 {
+                _CdkFloat32::canonicalize(self.0)
+            }
+        }
+
+        impl From<f32> for _CdkFloat32 {
+            fn from(value: f32) -> Self {
+                _CdkFloat32(_CdkFloat32::canonicalize(value))
+            }
+        }
+
+        impl From<_CdkFloat32> for f32 {
+            fn from(value: _CdkFloat32) -> Self {
+                value.0
+            }
+        }
+
+        impl std::ops::Deref for _CdkFloat32 {
+            type Target = f32;
+
+            fn deref(&self) -> &Self::Target {
+                &self.0
+            }
+        }
+
+        impl<'de> serde::Deserialize<'de> for _CdkFloat32 {
+            fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+            where
+                D: serde::Deserializer<'de>,
+            {
+                <f32 as serde::Deserialize>::deserialize(deserializer).map(_CdkFloat32::from)
+            }
+        }
+
+        impl std::fmt::Display for _CdkFloat32 {
+            fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+                std::fmt::Display::fmt(&self.0, f)
+            }
+        }
+
         impl std::cmp::Ord for _CdkFloat32 {
             fn cmp(&self, other: &Self) -> std::cmp::Ordering {
-                self.partial_cmp(other).unwrap_or(std::cmp::Ordering::Less)
+                self.canonical().total_cmp(&other.canonical())
             }
         }
 
         impl std::cmp::PartialOrd for _CdkFloat32 {
             fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
-                self.0.partial_cmp(&other.0)
+                Some(self.cmp(other))
             }
         }
 
@@ -22,7 +77,13 @@ pub fn generate() -> TokenStream {
 
         impl std::cmp::PartialEq for _CdkFloat32 {
             fn eq(&self, other: &Self) -> bool {
-                self.0.eq(&other.0)
+                self.cmp(other) == std::cmp::Ordering::Equal
+            }
+        }
+
+        impl std::hash::Hash for _CdkFloat32 {
+            fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
+                std::hash::Hash::hash(&self.canonical().to_bits(), state);
             }
         }
     }
@@ -0,0 +1,152 @@
+//! Shorthands for building ACT nodes by hand
+
+use super::VmConversionHooks;
+use crate::act::node::{
+    candid::{
+        service::Method, tuple::Elem, type_param::TypeParams, Array, Func, Opt, Primitive, Record,
+        Service, Tuple, TypeAlias, TypeRef, Variant,
+    },
+    node_parts::mode::Mode,
+    CandidType, Member, Param, ReturnType,
+};
+
+pub fn primitive(primitive: Primitive) -> CandidType {
+    CandidType::Primitive(primitive)
+}
+
+pub fn type_ref(name: &str) -> CandidType {
+    CandidType::TypeRef(TypeRef {
+        name: name.to_string(),
+        type_arguments: vec![],
+    })
+}
+
+pub fn opt(enclosed_type: CandidType) -> CandidType {
+    CandidType::Opt(Opt {
+        enclosed_type: Box::new(enclosed_type),
+    })
+}
+
+pub fn vec(enclosed_type: CandidType) -> CandidType {
+    CandidType::Array(Array {
+        enclosed_type: Box::new(enclosed_type),
+    })
+}
+
+pub fn member(name: &str, candid_type: CandidType) -> Member {
+    Member {
+        name: name.to_string(),
+        candid_type,
+        docs: None,
+        label_id: None,
+    }
+}
+
+/// A variant case without a payload
+pub fn case(name: &str) -> Member {
+    member(name, primitive(Primitive::Null))
+}
+
+pub fn record(name: Option<&str>, members: Vec<Member>) -> Record {
+    Record {
+        name: name.map(|name| name.to_string()),
+        members,
+        type_params: TypeParams(vec![]),
+        derives: vec![],
+        attributes: vec![],
+        docs: None,
+    }
+}
+
+pub fn variant(name: Option<&str>, members: Vec<Member>) -> Variant {
+    Variant {
+        name: name.map(|name| name.to_string()),
+        members,
+        type_params: TypeParams(vec![]),
+        derives: vec![],
+        attributes: vec![],
+        docs: None,
+    }
+}
+
+pub fn inline_record(members: Vec<Member>) -> CandidType {
+    CandidType::Record(record(None, members))
+}
+
+pub fn inline_variant(members: Vec<Member>) -> CandidType {
+    CandidType::Variant(variant(None, members))
+}
+
+pub fn inline_tuple(elems: Vec<CandidType>) -> CandidType {
+    CandidType::Tuple(Tuple {
+        name: None,
+        elems: elems
+            .into_iter()
+            .map(|candid_type| Elem { candid_type })
+            .collect(),
+        type_params: TypeParams(vec![]),
+        derives: vec![],
+        attributes: vec![],
+        docs: None,
+    })
+}
+
+/// A `variant { Ok : ok; Err : err }` result
+pub fn result(ok: CandidType, err: CandidType) -> CandidType {
+    inline_variant(vec![member("Ok", ok), member("Err", err)])
+}
+
+pub fn type_alias(name: &str, aliased_type: CandidType) -> TypeAlias {
+    TypeAlias {
+        name: name.to_string(),
+        aliased_type: Box::new(aliased_type),
+        type_params: TypeParams(vec![]),
+        docs: None,
+    }
+}
+
+pub fn param(name: &str, candid_type: CandidType) -> Param {
+    Param {
+        name: name.to_string(),
+        candid_type,
+        docs: None,
+    }
+}
+
+/// A service method. `Primitive::Void` as the return type means the method
+/// returns nothing.
+pub fn method(name: &str, mode: Mode, params: Vec<Param>, return_type: CandidType) -> Method {
+    Method::new(name.to_string(), mode, params, return_type)
+}
+
+pub fn service(name: &str, methods: Vec<Method>, hooks: VmConversionHooks) -> Service {
+    Service {
+        name: name.to_string(),
+        methods,
+        docs: None,
+        call_kinds: None,
+        to_vm_value: hooks.to_vm_value,
+        list_to_vm_value: hooks.list_to_vm_value,
+        from_vm_value: hooks.from_vm_value,
+        list_from_vm_value: hooks.list_from_vm_value,
+    }
+}
+
+pub fn func(
+    name: Option<&str>,
+    params: Vec<CandidType>,
+    return_type: CandidType,
+    mode: Mode,
+    hooks: VmConversionHooks,
+) -> Func {
+    Func {
+        name: name.map(|name| name.to_string()),
+        params,
+        return_type: Box::new(ReturnType::new(return_type)),
+        mode,
+        to_vm_value: hooks.to_vm_value,
+        list_to_vm_value: hooks.list_to_vm_value,
+        from_vm_value: hooks.from_vm_value,
+        list_from_vm_value: hooks.list_from_vm_value,
+    }
+}
@@ -0,0 +1,150 @@
+use super::{
+    builders::{
+        case, inline_record, inline_tuple, member, method, opt, param, primitive, record, result,
+        service, type_alias, type_ref, variant, vec,
+    },
+    VmConversionHooks,
+};
+use crate::act::{
+    node::{
+        candid::{service::Method, Primitive, Record, TypeAlias, Variant},
+        node_parts::mode::Mode,
+        CandidType,
+    },
+    CandidTypes,
+};
+
+/// The name of the ICRC-1 ledger service
+pub const SERVICE_NAME: &str = "icrc1";
+
+/// The ICRC-1 fungible token ledger interface. The types match the standard
+/// but are prefixed with `Icrc1` so that they don't collide with the types of
+/// other interfaces or of the canister.
+pub fn icrc1(hooks: VmConversionHooks) -> CandidTypes {
+    CandidTypes {
+        funcs: vec![],
+        records: records(),
+        services: vec![service(SERVICE_NAME, methods(), hooks)],
+        tuples: vec![],
+        type_aliases: type_aliases(),
+        variants: variants(),
+    }
+}
+
+pub(super) fn nat() -> CandidType {
+    primitive(Primitive::Nat)
+}
+
+pub(super) fn text() -> CandidType {
+    primitive(Primitive::String)
+}
+
+pub(super) fn error_code_record() -> CandidType {
+    inline_record(vec![member("error_code", nat()), member("message", text())])
+}
+
+pub(super) fn type_aliases() -> Vec<TypeAlias> {
+    vec![
+        type_alias("Icrc1Subaccount", primitive(Primitive::Blob)),
+        type_alias("Icrc1Timestamp", primitive(Primitive::Nat64)),
+    ]
+}
+
+pub(super) fn records() -> Vec<Record> {
+    vec![
+        record(
+            Some("Icrc1Account"),
+            vec![
+                member("owner", primitive(Primitive::Principal)),
+                member("subaccount", opt(type_ref("Icrc1Subaccount"))),
+            ],
+        ),
+        record(
+            Some("Icrc1TransferArg"),
+            vec![
+                member("from_subaccount", opt(type_ref("Icrc1Subaccount"))),
+                member("to", type_ref("Icrc1Account")),
+                member("amount", nat()),
+                member("fee", opt(nat())),
+                member("memo", opt(primitive(Primitive::Blob))),
+                member("created_at_time", opt(type_ref("Icrc1Timestamp"))),
+            ],
+        ),
+    ]
+}
+
+pub(super) fn variants() -> Vec<Variant> {
+    vec![
+        variant(
+            Some("Icrc1Value"),
+            vec![
+                member("Nat", nat()),
+                member("Int", primitive(Primitive::Int)),
+                member("Text", text()),
+                member("Blob", primitive(Primitive::Blob)),
+            ],
+        ),
+        variant(
+            Some("Icrc1TransferError"),
+            vec![
+                member("BadFee", inline_record(vec![member("expected_fee", nat())])),
+                member(
+                    "BadBurn",
+                    inline_record(vec![member("min_burn_amount", nat())]),
+                ),
+                member(
+                    "InsufficientFunds",
+                    inline_record(vec![member("balance", nat())]),
+                ),
+                case("TooOld"),
+                member(
+                    "CreatedInFuture",
+                    inline_record(vec![member("ledger_time", type_ref("Icrc1Timestamp"))]),
+                ),
+                member(
+                    "Duplicate",
+                    inline_record(vec![member("duplicate_of", nat())]),
+                ),
+                case("TemporarilyUnavailable"),
+                member("GenericError", error_code_record()),
+            ],
+        ),
+    ]
+}
+
+pub(super) fn methods() -> Vec<Method> {
+    let query =
+        |name: &str, return_type: CandidType| method(name, Mode::Query, vec![], return_type);
+
+    vec![
+        query(
+            "icrc1_metadata",
+            vec(inline_tuple(vec![text(), type_ref("Icrc1Value")])),
+        ),
+        query("icrc1_name", text()),
+        query("icrc1_symbol", text()),
+        query("icrc1_decimals", primitive(Primitive::Nat8)),
+        query("icrc1_fee", nat()),
+        query("icrc1_total_supply", nat()),
+        query("icrc1_minting_account", opt(type_ref("Icrc1Account"))),
+        method(
+            "icrc1_balance_of",
+            Mode::Query,
+            vec![param("account", type_ref("Icrc1Account"))],
+            nat(),
+        ),
+        method(
+            "icrc1_transfer",
+            Mode::Update,
+            vec![param("arg", type_ref("Icrc1TransferArg"))],
+            result(nat(), type_ref("Icrc1TransferError")),
+        ),
+        query(
+            "icrc1_supported_standards",
+            vec(inline_record(vec![
+                member("name", text()),
+                member("url", text()),
+            ])),
+        ),
+    ]
+}
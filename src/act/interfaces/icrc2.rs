@@ -0,0 +1,160 @@
+use super::{
+    builders::{
+        case, inline_record, member, method, opt, param, primitive, record, result, service,
+        type_ref, variant,
+    },
+    icrc1::{self, error_code_record, nat},
+    VmConversionHooks,
+};
+use crate::act::{
+    node::{
+        candid::{service::Method, Primitive, Record, Variant},
+        node_parts::mode::Mode,
+    },
+    CandidTypes,
+};
+
+/// The name of the ICRC-2 ledger service
+pub const SERVICE_NAME: &str = "icrc2";
+
+/// The ICRC-2 approve and transfer from interface. An ICRC-2 ledger also
+/// implements ICRC-1, so the service has the ICRC-1 methods as well and the
+/// ICRC-1 types are included. Don't extend a CDK's types with both this and
+/// [icrc1](super::icrc1::icrc1) since the ICRC-1 types would be defined twice.
+pub fn icrc2(hooks: VmConversionHooks) -> CandidTypes {
+    CandidTypes {
+        funcs: vec![],
+        records: [icrc1::records(), records()].concat(),
+        services: vec![service(
+            SERVICE_NAME,
+            [icrc1::methods(), methods()].concat(),
+            hooks,
+        )],
+        tuples: vec![],
+        type_aliases: icrc1::type_aliases(),
+        variants: [icrc1::variants(), variants()].concat(),
+    }
+}
+
+fn records() -> Vec<Record> {
+    vec![
+        record(
+            Some("Icrc2ApproveArgs"),
+            vec![
+                member("from_subaccount", opt(primitive(Primitive::Blob))),
+                member("spender", type_ref("Icrc1Account")),
+                member("amount", nat()),
+                member("expected_allowance", opt(nat())),
+                member("expires_at", opt(primitive(Primitive::Nat64))),
+                member("fee", opt(nat())),
+                member("memo", opt(primitive(Primitive::Blob))),
+                member("created_at_time", opt(primitive(Primitive::Nat64))),
+            ],
+        ),
+        record(
+            Some("Icrc2TransferFromArgs"),
+            vec![
+                member("spender_subaccount", opt(primitive(Primitive::Blob))),
+                member("from", type_ref("Icrc1Account")),
+                member("to", type_ref("Icrc1Account")),
+                member("amount", nat()),
+                member("fee", opt(nat())),
+                member("memo", opt(primitive(Primitive::Blob))),
+                member("created_at_time", opt(primitive(Primitive::Nat64))),
+            ],
+        ),
+        record(
+            Some("Icrc2AllowanceArgs"),
+            vec![
+                member("account", type_ref("Icrc1Account")),
+                member("spender", type_ref("Icrc1Account")),
+            ],
+        ),
+        record(
+            Some("Icrc2Allowance"),
+            vec![
+                member("allowance", nat()),
+                member("expires_at", opt(primitive(Primitive::Nat64))),
+            ],
+        ),
+    ]
+}
+
+fn variants() -> Vec<Variant> {
+    let ledger_time = || inline_record(vec![member("ledger_time", primitive(Primitive::Nat64))]);
+
+    vec![
+        variant(
+            Some("Icrc2ApproveError"),
+            vec![
+                member("BadFee", inline_record(vec![member("expected_fee", nat())])),
+                member(
+                    "InsufficientFunds",
+                    inline_record(vec![member("balance", nat())]),
+                ),
+                member(
+                    "AllowanceChanged",
+                    inline_record(vec![member("current_allowance", nat())]),
+                ),
+                member("Expired", ledger_time()),
+                case("TooOld"),
+                member("CreatedInFuture", ledger_time()),
+                member(
+                    "Duplicate",
+                    inline_record(vec![member("duplicate_of", nat())]),
+                ),
+                case("TemporarilyUnavailable"),
+                member("GenericError", error_code_record()),
+            ],
+        ),
+        variant(
+            Some("Icrc2TransferFromError"),
+            vec![
+                member("BadFee", inline_record(vec![member("expected_fee", nat())])),
+                member(
+                    "BadBurn",
+                    inline_record(vec![member("min_burn_amount", nat())]),
+                ),
+                member(
+                    "InsufficientFunds",
+                    inline_record(vec![member("balance", nat())]),
+                ),
+                member(
+                    "InsufficientAllowance",
+                    inline_record(vec![member("allowance", nat())]),
+                ),
+                case("TooOld"),
+                member("CreatedInFuture", ledger_time()),
+                member(
+                    "Duplicate",
+                    inline_record(vec![member("duplicate_of", nat())]),
+                ),
+                case("TemporarilyUnavailable"),
+                member("GenericError", error_code_record()),
+            ],
+        ),
+    ]
+}
+
+fn methods() -> Vec<Method> {
+    vec![
+        method(
+            "icrc2_approve",
+            Mode::Update,
+            vec![param("args", type_ref("Icrc2ApproveArgs"))],
+            result(nat(), type_ref("Icrc2ApproveError")),
+        ),
+        method(
+            "icrc2_transfer_from",
+            Mode::Update,
+            vec![param("args", type_ref("Icrc2TransferFromArgs"))],
+            result(nat(), type_ref("Icrc2TransferFromError")),
+        ),
+        method(
+            "icrc2_allowance",
+            Mode::Query,
+            vec![param("args", type_ref("Icrc2AllowanceArgs"))],
+            type_ref("Icrc2Allowance"),
+        ),
+    ]
+}
@@ -0,0 +1,171 @@
+use super::{
+    builders::{
+        case, inline_record, inline_tuple, member, method, opt, param, primitive, record, service,
+        type_alias, type_ref, variant, vec,
+    },
+    icrc1::{error_code_record, nat, text},
+    VmConversionHooks,
+};
+use crate::act::{
+    node::{
+        candid::{service::Method, Primitive, Record, TypeAlias, Variant},
+        node_parts::mode::Mode,
+        CandidType,
+    },
+    CandidTypes,
+};
+
+/// The name of the ICRC-7 NFT ledger service
+pub const SERVICE_NAME: &str = "icrc7";
+
+/// The ICRC-7 non-fungible token interface. The types match the standard but
+/// are prefixed with `Icrc7` so that they don't collide with the types of
+/// other interfaces or of the canister.
+pub fn icrc7(hooks: VmConversionHooks) -> CandidTypes {
+    CandidTypes {
+        funcs: vec![],
+        records: records(),
+        services: vec![service(SERVICE_NAME, methods(), hooks)],
+        tuples: vec![],
+        type_aliases: type_aliases(),
+        variants: variants(),
+    }
+}
+
+/// `vec record { text; Value }`
+fn metadata() -> CandidType {
+    vec(inline_tuple(vec![text(), type_ref("Icrc7Value")]))
+}
+
+fn type_aliases() -> Vec<TypeAlias> {
+    vec![type_alias("Icrc7Subaccount", primitive(Primitive::Blob))]
+}
+
+fn records() -> Vec<Record> {
+    vec![
+        record(
+            Some("Icrc7Account"),
+            vec![
+                member("owner", primitive(Primitive::Principal)),
+                member("subaccount", opt(type_ref("Icrc7Subaccount"))),
+            ],
+        ),
+        record(
+            Some("Icrc7TransferArg"),
+            vec![
+                member("from_subaccount", opt(primitive(Primitive::Blob))),
+                member("to", type_ref("Icrc7Account")),
+                member("token_id", nat()),
+                member("memo", opt(primitive(Primitive::Blob))),
+                member("created_at_time", opt(primitive(Primitive::Nat64))),
+            ],
+        ),
+    ]
+}
+
+fn variants() -> Vec<Variant> {
+    vec![
+        variant(
+            Some("Icrc7Value"),
+            vec![
+                member("Blob", primitive(Primitive::Blob)),
+                member("Text", text()),
+                member("Nat", nat()),
+                member("Int", primitive(Primitive::Int)),
+                member("Array", vec(type_ref("Icrc7Value"))),
+                member("Map", metadata()),
+            ],
+        ),
+        variant(
+            Some("Icrc7TransferResult"),
+            vec![
+                member("Ok", nat()),
+                member("Err", type_ref("Icrc7TransferError")),
+            ],
+        ),
+        variant(
+            Some("Icrc7TransferError"),
+            vec![
+                case("NonExistingTokenId"),
+                case("InvalidRecipient"),
+                case("Unauthorized"),
+                case("TooOld"),
+                member(
+                    "CreatedInFuture",
+                    inline_record(vec![member("ledger_time", primitive(Primitive::Nat64))]),
+                ),
+                member(
+                    "Duplicate",
+                    inline_record(vec![member("duplicate_of", nat())]),
+                ),
+                member("GenericError", error_code_record()),
+                member("GenericBatchError", error_code_record()),
+            ],
+        ),
+    ]
+}
+
+fn methods() -> Vec<Method> {
+    let query =
+        |name: &str, return_type: CandidType| method(name, Mode::Query, vec![], return_type);
+    let token_ids = || param("token_ids", vec(nat()));
+    let prev = || param("prev", opt(nat()));
+    let take = || param("take", opt(nat()));
+
+    vec![
+        query("icrc7_collection_metadata", metadata()),
+        query("icrc7_symbol", text()),
+        query("icrc7_name", text()),
+        query("icrc7_description", opt(text())),
+        query("icrc7_logo", opt(text())),
+        query("icrc7_total_supply", nat()),
+        query("icrc7_supply_cap", opt(nat())),
+        query("icrc7_max_query_batch_size", opt(nat())),
+        query("icrc7_max_update_batch_size", opt(nat())),
+        query("icrc7_default_take_value", opt(nat())),
+        query("icrc7_max_take_value", opt(nat())),
+        query("icrc7_max_memo_size", opt(nat())),
+        query(
+            "icrc7_atomic_batch_transfers",
+            opt(primitive(Primitive::Bool)),
+        ),
+        query("icrc7_tx_window", opt(nat())),
+        query("icrc7_permitted_drift", opt(nat())),
+        method(
+            "icrc7_token_metadata",
+            Mode::Query,
+            vec![token_ids()],
+            vec(opt(metadata())),
+        ),
+        method(
+            "icrc7_owner_of",
+            Mode::Query,
+            vec![token_ids()],
+            vec(opt(type_ref("Icrc7Account"))),
+        ),
+        method(
+            "icrc7_balance_of",
+            Mode::Query,
+            vec![param("accounts", vec(type_ref("Icrc7Account")))],
+            vec(nat()),
+        ),
+        method(
+            "icrc7_tokens",
+            Mode::Query,
+            vec![prev(), take()],
+            vec(nat()),
+        ),
+        method(
+            "icrc7_tokens_of",
+            Mode::Query,
+            vec![param("account", type_ref("Icrc7Account")), prev(), take()],
+            vec(nat()),
+        ),
+        method(
+            "icrc7_transfer",
+            Mode::Update,
+            vec![param("args", vec(type_ref("Icrc7TransferArg")))],
+            vec(opt(type_ref("Icrc7TransferResult"))),
+        ),
+    ]
+}
@@ -0,0 +1,461 @@
+use super::{
+    builders::{
+        case, func, inline_record, inline_variant, member, method, opt, param, primitive, record,
+        service, type_alias, type_ref, variant, vec,
+    },
+    VmConversionHooks,
+};
+use crate::act::{
+    node::{
+        candid::{service::Method, Func, Primitive, Record, TypeAlias, Variant},
+        node_parts::mode::Mode,
+        CandidType,
+    },
+    CandidTypes,
+};
+
+/// The name of the management canister's service. Its call functions are
+/// generated as `call_management_<method>` and friends and are called with
+/// `candid::Principal::management_canister()`.
+pub const SERVICE_NAME: &str = "management";
+
+/// The management canister (`aaaaa-aa`) with the type names of its candid
+/// interface, covering canister lifecycle, randomness, HTTPS outcalls,
+/// threshold signatures, and the bitcoin API
+pub fn management_canister(hooks: VmConversionHooks) -> CandidTypes {
+    CandidTypes {
+        funcs: vec![http_transform_function(hooks)],
+        records: records(),
+        services: vec![service(SERVICE_NAME, methods(), hooks)],
+        tuples: vec![],
+        type_aliases: type_aliases(),
+        variants: variants(),
+    }
+}
+
+/// The transform of an HTTPS outcall is a func, which needs the VM hooks
+fn http_transform_function(hooks: VmConversionHooks) -> Func {
+    func(
+        Some("http_transform_function"),
+        vec![inline_record(vec![
+            member("response", type_ref("http_request_result")),
+            member("context", blob()),
+        ])],
+        type_ref("http_request_result"),
+        Mode::Query,
+        hooks,
+    )
+}
+
+fn blob() -> CandidType {
+    primitive(Primitive::Blob)
+}
+
+fn nat() -> CandidType {
+    primitive(Primitive::Nat)
+}
+
+fn nat32() -> CandidType {
+    primitive(Primitive::Nat32)
+}
+
+fn nat64() -> CandidType {
+    primitive(Primitive::Nat64)
+}
+
+fn text() -> CandidType {
+    primitive(Primitive::String)
+}
+
+fn canister_id_record(name: &str) -> Record {
+    record(
+        Some(name),
+        vec![member("canister_id", type_ref("canister_id"))],
+    )
+}
+
+fn type_aliases() -> Vec<TypeAlias> {
+    vec![
+        type_alias("canister_id", primitive(Primitive::Principal)),
+        type_alias("wasm_module", blob()),
+        type_alias("raw_rand_result", blob()),
+        type_alias("satoshi", nat64()),
+        type_alias("millisatoshi_per_byte", nat64()),
+        type_alias("bitcoin_address", text()),
+        type_alias("block_hash", blob()),
+        type_alias("bitcoin_get_balance_result", type_ref("satoshi")),
+        type_alias(
+            "bitcoin_get_current_fee_percentiles_result",
+            vec(type_ref("millisatoshi_per_byte")),
+        ),
+    ]
+}
+
+fn variants() -> Vec<Variant> {
+    vec![
+        variant(
+            Some("log_visibility"),
+            vec![case("controllers"), case("public")],
+        ),
+        variant(
+            Some("canister_install_mode"),
+            vec![
+                case("install"),
+                case("reinstall"),
+                member(
+                    "upgrade",
+                    opt(inline_record(vec![
+                        member("skip_pre_upgrade", opt(primitive(Primitive::Bool))),
+                        member(
+                            "wasm_memory_persistence",
+                            opt(inline_variant(vec![case("keep"), case("replace")])),
+                        ),
+                    ])),
+                ),
+            ],
+        ),
+        variant(Some("ecdsa_curve"), vec![case("secp256k1")]),
+        variant(
+            Some("schnorr_algorithm"),
+            vec![case("bip340secp256k1"), case("ed25519")],
+        ),
+        variant(
+            Some("bitcoin_network"),
+            vec![case("mainnet"), case("testnet")],
+        ),
+    ]
+}
+
+fn records() -> Vec<Record> {
+    let sender_canister_version = || member("sender_canister_version", opt(nat64()));
+    let ecdsa_key_id = || {
+        inline_record(vec![
+            member("curve", type_ref("ecdsa_curve")),
+            member("name", text()),
+        ])
+    };
+    let schnorr_key_id = || {
+        inline_record(vec![
+            member("algorithm", type_ref("schnorr_algorithm")),
+            member("name", text()),
+        ])
+    };
+
+    vec![
+        record(
+            Some("canister_settings"),
+            vec![
+                member("controllers", opt(vec(primitive(Primitive::Principal)))),
+                member("compute_allocation", opt(nat())),
+                member("memory_allocation", opt(nat())),
+                member("freezing_threshold", opt(nat())),
+                member("reserved_cycles_limit", opt(nat())),
+                member("log_visibility", opt(type_ref("log_visibility"))),
+                member("wasm_memory_limit", opt(nat())),
+            ],
+        ),
+        record(
+            Some("definite_canister_settings"),
+            vec![
+                member("controllers", vec(primitive(Primitive::Principal))),
+                member("compute_allocation", nat()),
+                member("memory_allocation", nat()),
+                member("freezing_threshold", nat()),
+                member("reserved_cycles_limit", nat()),
+                member("log_visibility", type_ref("log_visibility")),
+                member("wasm_memory_limit", nat()),
+            ],
+        ),
+        record(
+            Some("create_canister_args"),
+            vec![
+                member("settings", opt(type_ref("canister_settings"))),
+                sender_canister_version(),
+            ],
+        ),
+        canister_id_record("create_canister_result"),
+        record(
+            Some("update_settings_args"),
+            vec![
+                member("canister_id", primitive(Primitive::Principal)),
+                member("settings", type_ref("canister_settings")),
+                sender_canister_version(),
+            ],
+        ),
+        record(
+            Some("install_code_args"),
+            vec![
+                member("mode", type_ref("canister_install_mode")),
+                member("canister_id", type_ref("canister_id")),
+                member("wasm_module", type_ref("wasm_module")),
+                member("arg", blob()),
+                sender_canister_version(),
+            ],
+        ),
+        record(
+            Some("uninstall_code_args"),
+            vec![
+                member("canister_id", type_ref("canister_id")),
+                sender_canister_version(),
+            ],
+        ),
+        canister_id_record("start_canister_args"),
+        canister_id_record("stop_canister_args"),
+        canister_id_record("canister_status_args"),
+        record(
+            Some("canister_status_result"),
+            vec![
+                member(
+                    "status",
+                    inline_variant(vec![case("running"), case("stopping"), case("stopped")]),
+                ),
+                member("settings", type_ref("definite_canister_settings")),
+                member("module_hash", opt(blob())),
+                member("memory_size", nat()),
+                member("cycles", nat()),
+                member("reserved_cycles", nat()),
+                member("idle_cycles_burned_per_day", nat()),
+            ],
+        ),
+        canister_id_record("delete_canister_args"),
+        canister_id_record("deposit_cycles_args"),
+        record(
+            Some("provisional_create_canister_with_cycles_args"),
+            vec![
+                member("amount", opt(nat())),
+                member("settings", opt(type_ref("canister_settings"))),
+                member("specified_id", opt(type_ref("canister_id"))),
+                sender_canister_version(),
+            ],
+        ),
+        record(
+            Some("provisional_top_up_canister_args"),
+            vec![
+                member("canister_id", type_ref("canister_id")),
+                member("amount", nat()),
+            ],
+        ),
+        record(
+            Some("http_header"),
+            vec![member("name", text()), member("value", text())],
+        ),
+        record(
+            Some("http_request_args"),
+            vec![
+                member("url", text()),
+                member("max_response_bytes", opt(nat64())),
+                member(
+                    "method",
+                    inline_variant(vec![case("get"), case("head"), case("post")]),
+                ),
+                member("headers", vec(type_ref("http_header"))),
+                member("body", opt(blob())),
+                member(
+                    "transform",
+                    opt(inline_record(vec![
+                        member("function", type_ref("http_transform_function")),
+                        member("context", blob()),
+                    ])),
+                ),
+            ],
+        ),
+        record(
+            Some("http_request_result"),
+            vec![
+                member("status", nat()),
+                member("headers", vec(type_ref("http_header"))),
+                member("body", blob()),
+            ],
+        ),
+        record(
+            Some("ecdsa_public_key_args"),
+            vec![
+                member("canister_id", opt(type_ref("canister_id"))),
+                member("derivation_path", vec(blob())),
+                member("key_id", ecdsa_key_id()),
+            ],
+        ),
+        record(
+            Some("ecdsa_public_key_result"),
+            vec![member("public_key", blob()), member("chain_code", blob())],
+        ),
+        record(
+            Some("sign_with_ecdsa_args"),
+            vec![
+                member("message_hash", blob()),
+                member("derivation_path", vec(blob())),
+                member("key_id", ecdsa_key_id()),
+            ],
+        ),
+        record(
+            Some("sign_with_ecdsa_result"),
+            vec![member("signature", blob())],
+        ),
+        record(
+            Some("schnorr_public_key_args"),
+            vec![
+                member("canister_id", opt(type_ref("canister_id"))),
+                member("derivation_path", vec(blob())),
+                member("key_id", schnorr_key_id()),
+            ],
+        ),
+        record(
+            Some("schnorr_public_key_result"),
+            vec![member("public_key", blob()), member("chain_code", blob())],
+        ),
+        record(
+            Some("sign_with_schnorr_args"),
+            vec![
+                member("message", blob()),
+                member("derivation_path", vec(blob())),
+                member("key_id", schnorr_key_id()),
+            ],
+        ),
+        record(
+            Some("sign_with_schnorr_result"),
+            vec![member("signature", blob())],
+        ),
+        record(
+            Some("outpoint"),
+            vec![member("txid", blob()), member("vout", nat32())],
+        ),
+        record(
+            Some("utxo"),
+            vec![
+                member("outpoint", type_ref("outpoint")),
+                member("value", type_ref("satoshi")),
+                member("height", nat32()),
+            ],
+        ),
+        record(
+            Some("bitcoin_get_balance_args"),
+            vec![
+                member("address", type_ref("bitcoin_address")),
+                member("network", type_ref("bitcoin_network")),
+                member("min_confirmations", opt(nat32())),
+            ],
+        ),
+        record(
+            Some("bitcoin_get_utxos_args"),
+            vec![
+                member("address", type_ref("bitcoin_address")),
+                member("network", type_ref("bitcoin_network")),
+                member(
+                    "filter",
+                    opt(inline_variant(vec![
+                        member("min_confirmations", nat32()),
+                        member("page", blob()),
+                    ])),
+                ),
+            ],
+        ),
+        record(
+            Some("bitcoin_get_utxos_result"),
+            vec![
+                member("utxos", vec(type_ref("utxo"))),
+                member("tip_block_hash", type_ref("block_hash")),
+                member("tip_height", nat32()),
+                member("next_page", opt(blob())),
+            ],
+        ),
+        record(
+            Some("bitcoin_get_current_fee_percentiles_args"),
+            vec![member("network", type_ref("bitcoin_network"))],
+        ),
+        record(
+            Some("bitcoin_send_transaction_args"),
+            vec![
+                member("transaction", blob()),
+                member("network", type_ref("bitcoin_network")),
+            ],
+        ),
+    ]
+}
+
+fn methods() -> Vec<Method> {
+    let update = |name: &str, arg: Option<&str>, result: Option<&str>| {
+        method(
+            name,
+            Mode::Update,
+            arg.map(|arg| vec![param("arg", type_ref(arg))])
+                .unwrap_or_default(),
+            result.map(type_ref).unwrap_or(primitive(Primitive::Void)),
+        )
+    };
+
+    vec![
+        update(
+            "create_canister",
+            Some("create_canister_args"),
+            Some("create_canister_result"),
+        ),
+        update("update_settings", Some("update_settings_args"), None),
+        update("install_code", Some("install_code_args"), None),
+        update("uninstall_code", Some("uninstall_code_args"), None),
+        update("start_canister", Some("start_canister_args"), None),
+        update("stop_canister", Some("stop_canister_args"), None),
+        update(
+            "canister_status",
+            Some("canister_status_args"),
+            Some("canister_status_result"),
+        ),
+        update("delete_canister", Some("delete_canister_args"), None),
+        update("deposit_cycles", Some("deposit_cycles_args"), None),
+        update("raw_rand", None, Some("raw_rand_result")),
+        update(
+            "http_request",
+            Some("http_request_args"),
+            Some("http_request_result"),
+        ),
+        update(
+            "ecdsa_public_key",
+            Some("ecdsa_public_key_args"),
+            Some("ecdsa_public_key_result"),
+        ),
+        update(
+            "sign_with_ecdsa",
+            Some("sign_with_ecdsa_args"),
+            Some("sign_with_ecdsa_result"),
+        ),
+        update(
+            "schnorr_public_key",
+            Some("schnorr_public_key_args"),
+            Some("schnorr_public_key_result"),
+        ),
+        update(
+            "sign_with_schnorr",
+            Some("sign_with_schnorr_args"),
+            Some("sign_with_schnorr_result"),
+        ),
+        update(
+            "bitcoin_get_balance",
+            Some("bitcoin_get_balance_args"),
+            Some("bitcoin_get_balance_result"),
+        ),
+        update(
+            "bitcoin_get_utxos",
+            Some("bitcoin_get_utxos_args"),
+            Some("bitcoin_get_utxos_result"),
+        ),
+        update(
+            "bitcoin_send_transaction",
+            Some("bitcoin_send_transaction_args"),
+            None,
+        ),
+        update(
+            "bitcoin_get_current_fee_percentiles",
+            Some("bitcoin_get_current_fee_percentiles_args"),
+            Some("bitcoin_get_current_fee_percentiles_result"),
+        ),
+        update(
+            "provisional_create_canister_with_cycles",
+            Some("provisional_create_canister_with_cycles_args"),
+            Some("create_canister_result"),
+        ),
+        update(
+            "provisional_top_up_canister",
+            Some("provisional_top_up_canister_args"),
+            None,
+        ),
+    ]
+}
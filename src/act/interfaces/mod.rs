@@ -0,0 +1,48 @@
+//! Prebuilt ACT fragments for interfaces that many canisters call. Each
+//! fragment is a [CandidTypes](crate::act::CandidTypes) with the interface's
+//! service and all of its records, variants and aliases, which a CDK adds to
+//! its own candid types with [CandidTypes::extend](crate::act::CandidTypes::extend).
+//! The methods of a fragment's service can also be turned into canister
+//! method skeletons with [server_methods] to implement the interface.
+
+use proc_macro2::TokenStream;
+
+pub mod builders;
+pub mod icrc1;
+pub mod icrc2;
+pub mod icrc7;
+pub mod management_canister;
+pub mod server;
+
+pub use icrc1::icrc1;
+pub use icrc2::icrc2;
+pub use icrc7::icrc7;
+pub use management_canister::management_canister;
+pub use server::server_methods;
+
+/// The VM conversion hooks that the services and funcs of an interface are
+/// generated with. See [Service](crate::act::node::candid::Service).
+#[derive(Clone, Copy)]
+pub struct VmConversionHooks {
+    pub to_vm_value: fn(String) -> TokenStream,
+    pub list_to_vm_value: fn(String) -> TokenStream,
+    pub from_vm_value: fn(String) -> TokenStream,
+    pub list_from_vm_value: fn(String) -> TokenStream,
+}
+
+impl VmConversionHooks {
+    /// Hooks that generate nothing, for candid types that are only compared
+    /// and never declared
+    pub fn none() -> VmConversionHooks {
+        fn no_vm_conversion(_: String) -> TokenStream {
+            TokenStream::new()
+        }
+
+        VmConversionHooks {
+            to_vm_value: no_vm_conversion,
+            list_to_vm_value: no_vm_conversion,
+            from_vm_value: no_vm_conversion,
+            list_from_vm_value: no_vm_conversion,
+        }
+    }
+}
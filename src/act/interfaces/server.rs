@@ -0,0 +1,39 @@
+use proc_macro2::TokenStream;
+
+use crate::act::node::{
+    candid::{service::Method, Service},
+    canister_method::{QueryMethod, QueryOrUpdateDefinition, UpdateMethod},
+    node_parts::mode::Mode,
+};
+
+/// Turns the methods of an interface's service into canister method
+/// skeletons, so that a CDK can implement the interface instead of calling
+/// it. `generate_body` is called for every method and returns its body, which
+/// has the method's params in scope. Oneway methods become update methods.
+pub fn server_methods(
+    service: &Service,
+    generate_body: impl Fn(&Method) -> TokenStream,
+) -> (Vec<QueryMethod>, Vec<UpdateMethod>) {
+    let mut query_methods = vec![];
+    let mut update_methods = vec![];
+
+    for method in &service.methods {
+        let mut definition = QueryOrUpdateDefinition::new(
+            false,
+            false,
+            None,
+            method.name.clone(),
+            method.params.clone(),
+            (*method.return_type).clone(),
+            generate_body(method),
+        );
+        definition.docs = method.docs.clone();
+
+        match method.mode {
+            Mode::Query | Mode::CompositeQuery => query_methods.push(QueryMethod { definition }),
+            Mode::Update | Mode::Oneway => update_methods.push(UpdateMethod { definition }),
+        }
+    }
+
+    (query_methods, update_methods)
+}
@@ -0,0 +1,288 @@
+use std::collections::BTreeSet;
+
+use proc_macro2::{Spacing, TokenStream, TokenTree};
+
+use crate::act::target_version::TargetVersion;
+
+/// The Cargo.toml of the generated canister crate. The dependencies of the
+/// generated code are found by [generate_cargo_toml], so only the
+/// dependencies of the CDK's custom Rust, like its VM, need to be listed.
+#[derive(Clone, Debug)]
+pub struct Manifest {
+    pub package_name: String,
+    /// Added to the detected dependencies. A dependency with the same name
+    /// as a detected one replaces it.
+    pub dependencies: Vec<Dependency>,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Dependency {
+    pub name: String,
+    pub version: String,
+    pub features: Vec<String>,
+    /// A cfg the dependency is limited to, like `target_os = "wasi"`
+    pub target: Option<String>,
+}
+
+impl Dependency {
+    pub fn new(name: &str, version: &str) -> Dependency {
+        Dependency {
+            name: name.to_string(),
+            version: version.to_string(),
+            features: vec![],
+            target: None,
+        }
+    }
+
+    pub fn with_features(mut self, features: &[&str]) -> Dependency {
+        self.features = features.iter().map(|feature| feature.to_string()).collect();
+        self
+    }
+
+    pub fn for_target(mut self, target: &str) -> Dependency {
+        self.target = Some(target.to_string());
+        self
+    }
+
+    fn to_toml(&self) -> String {
+        if self.features.is_empty() {
+            format!("{} = {}", self.name, quote_string(&self.version))
+        } else {
+            let features: Vec<_> = self
+                .features
+                .iter()
+                .map(|feature| quote_string(feature))
+                .collect();
+
+            format!(
+                "{} = {{ version = {}, features = [{}] }}",
+                self.name,
+                quote_string(&self.version),
+                features.join(", ")
+            )
+        }
+    }
+}
+
+/// A canister entry of a dfx.json
+#[derive(Clone, Debug)]
+pub struct DfxCanister {
+    pub name: String,
+    /// The package name of the canister's Cargo.toml
+    pub package: String,
+    /// The path of the canister's .did file, e.g. one written with
+    /// [did](crate::act::did)
+    pub candid: String,
+}
+
+/// Generates a Cargo.toml for `lib_file`, the output of
+/// [AbstractCanisterTree::to_token_stream](crate::act::AbstractCanisterTree::to_token_stream).
+/// Its dependencies and their features are derived from the crate paths
+/// used in `lib_file`, followed by the [Manifest::dependencies].
+pub fn generate_cargo_toml(
+    manifest: &Manifest,
+    lib_file: &TokenStream,
+    target_version: TargetVersion,
+) -> String {
+    let dependencies: Vec<_> = detect_dependencies(lib_file, target_version)
+        .into_iter()
+        .filter(|detected| {
+            !manifest
+                .dependencies
+                .iter()
+                .any(|dependency| dependency.name == detected.name)
+        })
+        .chain(manifest.dependencies.iter().cloned())
+        .collect();
+
+    let mut cargo_toml = format!(
+        "[package]\nname = {}\nversion = \"0.0.0\"\nedition = \"2021\"\n\n[lib]\ncrate-type = [\"cdylib\"]\n\n[dependencies]\n",
+        quote_string(&manifest.package_name)
+    );
+
+    for dependency in dependencies
+        .iter()
+        .filter(|dependency| dependency.target.is_none())
+    {
+        cargo_toml.push_str(&format!("{}\n", dependency.to_toml()));
+    }
+
+    let targets: BTreeSet<_> = dependencies
+        .iter()
+        .filter_map(|dependency| dependency.target.as_ref())
+        .collect();
+
+    for target in targets {
+        cargo_toml.push_str(&format!("\n[target.'cfg({})'.dependencies]\n", target));
+
+        for dependency in dependencies
+            .iter()
+            .filter(|dependency| dependency.target.as_ref() == Some(target))
+        {
+            cargo_toml.push_str(&format!("{}\n", dependency.to_toml()));
+        }
+    }
+
+    cargo_toml
+}
+
+/// The dependencies of the crates whose paths are used in `lib_file`. Paths
+/// of crates that the framework doesn't generate, like the CDK's VM, are
+/// ignored.
+pub fn detect_dependencies(
+    lib_file: &TokenStream,
+    target_version: TargetVersion,
+) -> Vec<Dependency> {
+    let mut paths = BTreeSet::new();
+    collect_paths(lib_file.clone(), &mut paths);
+
+    let uses = |root: &str| paths.iter().any(|(path_root, _)| path_root == root);
+    let uses_module = |root: &str, module: &str| {
+        paths
+            .iter()
+            .any(|(path_root, path_module)| path_root == root && path_module == module)
+    };
+
+    let mut dependencies = vec![];
+
+    if uses("ic_cdk") {
+        dependencies.push(match target_version {
+            TargetVersion::IcCdk0_6 if uses_module("ic_cdk", "timer") => {
+                Dependency::new("ic-cdk", "0.6.8").with_features(&["timers"])
+            }
+            TargetVersion::IcCdk0_6 => Dependency::new("ic-cdk", "0.6.8"),
+            TargetVersion::IcCdk0_18 => Dependency::new("ic-cdk", "0.18"),
+        });
+    }
+
+    let known_dependencies = vec![
+        (
+            "ic_cdk_macros",
+            match target_version {
+                TargetVersion::IcCdk0_6 => Dependency::new("ic-cdk-macros", "0.6.8"),
+                TargetVersion::IcCdk0_18 => Dependency::new("ic-cdk-macros", "0.18"),
+            },
+        ),
+        ("ic_cdk_timers", Dependency::new("ic-cdk-timers", "0.12")),
+        (
+            "candid",
+            match target_version {
+                TargetVersion::IcCdk0_6 => Dependency::new("candid", "0.8.4"),
+                TargetVersion::IcCdk0_18 => Dependency::new("candid", "0.10"),
+            },
+        ),
+        ("candid_parser", Dependency::new("candid_parser", "0.1")),
+        (
+            "serde",
+            Dependency::new("serde", "1.0").with_features(&["derive"]),
+        ),
+        ("rand", Dependency::new("rand", "0.8")),
+        (
+            "getrandom",
+            Dependency::new("getrandom", "0.2").with_features(&["custom"]),
+        ),
+        (
+            "ic_wasi_polyfill",
+            Dependency::new("ic-wasi-polyfill", "0.6").for_target("target_os = \"wasi\""),
+        ),
+        (
+            "ic_certified_map",
+            match target_version {
+                TargetVersion::IcCdk0_6 => Dependency::new("ic-certified-map", "0.3"),
+                TargetVersion::IcCdk0_18 => Dependency::new("ic-certified-map", "0.4"),
+            },
+        ),
+        ("serde_cbor", Dependency::new("serde_cbor", "0.11")),
+        ("sha2", Dependency::new("sha2", "0.10")),
+        ("base64", Dependency::new("base64", "0.13")),
+    ];
+
+    dependencies.extend(
+        known_dependencies
+            .into_iter()
+            .filter(|(root, _)| uses(root))
+            .map(|(_, dependency)| dependency),
+    );
+
+    dependencies
+}
+
+/// Generates a dfx.json with an entry for each canister
+pub fn generate_dfx_json(canisters: &[DfxCanister]) -> String {
+    let entries: Vec<_> = canisters
+        .iter()
+        .map(|canister| {
+            format!(
+                "    {}: {{\n      \"type\": \"rust\",\n      \"package\": {},\n      \"candid\": {}\n    }}",
+                quote_string(&canister.name),
+                quote_string(&canister.package),
+                quote_string(&canister.candid)
+            )
+        })
+        .collect();
+
+    format!(
+        "{{\n  \"canisters\": {{\n{}\n  }}\n}}\n",
+        entries.join(",\n")
+    )
+}
+
+/// Collects the first two segments of every path that starts at a crate
+/// root, like `ic_cdk::timer` in `ic_cdk::timer::set_timer`. Paths that
+/// start after another segment, a type or a generic aren't roots.
+fn collect_paths(tokens: TokenStream, paths: &mut BTreeSet<(String, String)>) {
+    let tokens: Vec<_> = tokens.into_iter().collect();
+
+    let is_path_separator = |index: usize| match (tokens.get(index), tokens.get(index + 1)) {
+        (Some(TokenTree::Punct(first)), Some(TokenTree::Punct(second))) => {
+            first.as_char() == ':' && first.spacing() == Spacing::Joint && second.as_char() == ':'
+        }
+        _ => false,
+    };
+
+    for (index, token) in tokens.iter().enumerate() {
+        match token {
+            TokenTree::Group(group) => collect_paths(group.stream(), paths),
+            TokenTree::Ident(ident) if is_path_separator(index + 1) => {
+                let is_root = match index.checked_sub(2) {
+                    Some(separator_index) if is_path_separator(separator_index) => {
+                        match separator_index
+                            .checked_sub(1)
+                            .and_then(|index| tokens.get(index))
+                        {
+                            Some(TokenTree::Ident(_)) => false,
+                            Some(TokenTree::Punct(punct)) => punct.as_char() != '>',
+                            _ => true,
+                        }
+                    }
+                    _ => true,
+                };
+
+                if is_root {
+                    let module = match tokens.get(index + 3) {
+                        Some(TokenTree::Ident(module)) => module.to_string(),
+                        _ => String::new(),
+                    };
+
+                    paths.insert((ident.to_string(), module));
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+/// Quotes `string` as a string that is valid in both TOML and JSON
+fn quote_string(string: &str) -> String {
+    let escaped: String = string
+        .chars()
+        .map(|char| match char {
+            '"' => "\\\"".to_string(),
+            '\\' => "\\\\".to_string(),
+            char if char.is_control() => format!("\\u{:04x}", char as u32),
+            char => char.to_string(),
+        })
+        .collect();
+
+    format!("\"{}\"", escaped)
+}
@@ -0,0 +1,138 @@
+use proc_macro2::{Literal, TokenStream};
+use quote::{format_ident, quote};
+
+use crate::act::target_version::TargetVersion;
+
+/// The metadata embedded in the generated canister's Wasm as custom sections
+/// named `icp:public <name>` or `icp:private <name>`. Tooling like dfx and
+/// the IC dashboard read them from the module without running it. Public
+/// sections can be read by anyone, private ones only by the controllers.
+#[derive(Clone, Debug)]
+pub struct Metadata {
+    /// Embeds the canister's .did file as `icp:public candid:service` and
+    /// the types of its init params as `icp:public candid:args`
+    pub candid: bool,
+    /// Generates a `__get_candid_interface_tmp_hack` query that returns the
+    /// .did file, which the Candid UI uses to discover the interface
+    pub candid_interface_query: bool,
+    /// Sections declared by the CDK, such as a version or a git commit
+    pub custom_sections: Vec<CustomSection>,
+}
+
+impl Default for Metadata {
+    fn default() -> Self {
+        Metadata {
+            candid: true,
+            candid_interface_query: false,
+            custom_sections: vec![],
+        }
+    }
+}
+
+impl Metadata {
+    /// The names of the canister methods generated for the metadata
+    pub fn get_method_names(&self) -> Vec<String> {
+        if self.candid_interface_query {
+            vec!["__get_candid_interface_tmp_hack".to_string()]
+        } else {
+            vec![]
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct CustomSection {
+    pub name: String,
+    pub content: Vec<u8>,
+    pub visibility: Visibility,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Visibility {
+    Public,
+    Private,
+}
+
+impl CustomSection {
+    pub fn public(name: &str, content: &str) -> CustomSection {
+        CustomSection {
+            name: name.to_string(),
+            content: content.as_bytes().to_vec(),
+            visibility: Visibility::Public,
+        }
+    }
+
+    pub fn private(name: &str, content: &str) -> CustomSection {
+        CustomSection {
+            name: name.to_string(),
+            content: content.as_bytes().to_vec(),
+            visibility: Visibility::Private,
+        }
+    }
+
+    fn get_section_name(&self) -> String {
+        match self.visibility {
+            Visibility::Public => format!("icp:public {}", self.name),
+            Visibility::Private => format!("icp:private {}", self.name),
+        }
+    }
+}
+
+/// Generates a static in a `link_section` for each custom section, and the
+/// `__get_candid_interface_tmp_hack` query when it's enabled. `did` and
+/// `init_args` are the .did file and init args from
+/// [did](crate::act::did).
+pub fn generate_metadata_code(
+    metadata: &Metadata,
+    target_version: TargetVersion,
+    did: &str,
+    init_args: &str,
+) -> TokenStream {
+    let candid_sections = if metadata.candid {
+        vec![
+            CustomSection::public("candid:service", did),
+            CustomSection::public("candid:args", init_args),
+        ]
+    } else {
+        vec![]
+    };
+
+    let sections = candid_sections
+        .iter()
+        .chain(&metadata.custom_sections)
+        .enumerate()
+        .map(|(index, custom_section)| {
+            let static_name = format_ident!("_CDK_CUSTOM_SECTION_{}", index);
+            let section_name = custom_section.get_section_name();
+            let length = custom_section.content.len();
+            let content = Literal::byte_string(&custom_section.content);
+
+            quote! {
+                #[cfg(target_arch = "wasm32")]
+                #[link_section = #section_name]
+                #[used]
+                static #static_name: [u8; #length] = *#content;
+            }
+        });
+
+    let candid_interface_query = if metadata.candid_interface_query {
+        let canister_method_attribute = target_version.generate_canister_method_attribute(
+            "query",
+            quote!(name = "__get_candid_interface_tmp_hack"),
+        );
+
+        quote! {
+            #canister_method_attribute
+            fn _cdk_get_candid_interface_tmp_hack() -> String {
+                #did.to_string()
+            }
+        }
+    } else {
+        quote!()
+    };
+
+    quote! {
+        #(#sections)*
+        #candid_interface_query
+    }
+}
@@ -11,10 +11,19 @@ pub mod abstract_canister_tree;
 pub mod candid_file_generation;
 pub mod candid_types;
 pub mod canister_methods;
+pub mod certification;
+pub mod compatibility;
 pub mod declaration;
+pub mod did;
+pub mod docs;
+pub mod dynamic_call;
 pub mod float32;
 pub mod float64;
+pub mod interfaces;
+pub mod manifest;
+pub mod metadata;
 pub mod node;
 pub mod random;
+pub mod target_version;
 pub mod type_annotation;
 pub mod vm_value_conversion;
@@ -0,0 +1,402 @@
+//! The candid derive only writes text labels for named fields, so a record or
+//! variant with a numeric label, e.g. `record { 0: nat; 42: text }`, would
+//! be encoded with the hash of its Rust field name instead. These types get
+//! hand-written `candid::CandidType` and `serde::Deserialize` impls that use
+//! `Label::Id` for members with a [label_id](Member::label_id) and
+//! `Label::Named` for every other member.
+
+use proc_macro2::{Ident, Literal, TokenStream};
+use quote::{format_ident, quote};
+
+use crate::{
+    act::{
+        node::{candid::type_param::TypeParams, Context, Member},
+        ToTypeAnnotation,
+    },
+    label,
+    traits::ToIdent,
+};
+
+/// Whether the record or variant needs the impls of this module
+pub fn has_label_ids(members: &[Member]) -> bool {
+    members.iter().any(|member| member.label_id.is_some())
+}
+
+struct Field {
+    id: u32,
+    label: TokenStream,
+    name: String,
+    rust_name: Ident,
+    value_type: Option<TokenStream>,
+}
+
+pub fn generate_record_impls(
+    context: &Context,
+    record_ident: &Ident,
+    type_params: &TypeParams,
+    members: &[Member],
+    parent_name: &str,
+) -> TokenStream {
+    let fields = to_sorted_fields(context, members, |member| {
+        let type_annotation = member.to_type_annotation(context, parent_name.to_string());
+        Some(quote!(Box<#type_annotation>))
+    });
+    let candid_type = generate_type(context, "Record", &fields);
+    let rust_names: Vec<_> = fields.iter().map(|field| &field.rust_name).collect();
+    let idl_serialize = quote! {
+        let mut compound = serializer.serialize_struct()?;
+        #(candid::types::Compound::serialize_element(&mut compound, &self.#rust_names)?;)*
+        Ok(())
+    };
+
+    let field_variables: Vec<_> = (0..fields.len())
+        .map(|index| format_ident!("field_{}", index))
+        .collect();
+    let field_names: Vec<_> = fields
+        .iter()
+        .map(|field| field.rust_name.to_string())
+        .collect();
+    let value_types = fields.iter().map(|field| &field.value_type);
+    let field_keys = generate_field_keys(fields.len());
+    let visit = quote! {
+        fn visit_map<A>(self, mut map: A) -> Result<Self::Value, A::Error>
+        where
+            A: serde::de::MapAccess<'de>,
+        {
+            #(let mut #field_variables: Option<#value_types> = None;)*
+
+            while let Some(key) = serde::de::MapAccess::next_key::<_CdkFieldKey>(&mut map)? {
+                match key {
+                    #(#field_keys => {
+                        #field_variables = Some(serde::de::MapAccess::next_value(&mut map)?);
+                    })*
+                    _CdkFieldKey::Ignored => {
+                        serde::de::MapAccess::next_value::<serde::de::IgnoredAny>(&mut map)?;
+                    }
+                }
+            }
+
+            Ok(#record_ident {
+                #(#rust_names: #field_variables.ok_or_else(|| serde::de::Error::missing_field(#field_names))?),*
+            })
+        }
+    };
+    let record_name = record_ident.to_string();
+    let deserialize = quote! {
+        serde::Deserializer::deserialize_struct(
+            deserializer,
+            #record_name,
+            &[#(#field_names),*],
+            visitor
+        )
+    };
+
+    generate_impls(
+        record_ident,
+        type_params,
+        &fields,
+        candid_type,
+        idl_serialize,
+        visit,
+        deserialize,
+    )
+}
+
+pub fn generate_variant_impls(
+    context: &Context,
+    variant_ident: &Ident,
+    type_params: &TypeParams,
+    members: &[Member],
+    parent_name: &str,
+) -> TokenStream {
+    let cases = to_sorted_fields(context, members, |member| {
+        member.to_variant_value_type(context, parent_name.to_string())
+    });
+    let candid_type = generate_type(context, "Variant", &cases);
+    let serialize_arms = cases.iter().enumerate().map(|(index, case)| {
+        let rust_name = &case.rust_name;
+        let index = index as u64;
+
+        match case.value_type {
+            Some(_) => quote! {
+                #variant_ident::#rust_name(ref value) => {
+                    let mut compound = serializer.serialize_variant(#index)?;
+                    candid::types::Compound::serialize_element(&mut compound, value)?;
+                }
+            },
+            None => quote! {
+                #variant_ident::#rust_name => {
+                    serializer.serialize_variant(#index)?;
+                }
+            },
+        }
+    });
+    let idl_serialize = quote! {
+        match *self {
+            #(#serialize_arms)*
+        }
+        Ok(())
+    };
+
+    let field_keys = generate_field_keys(cases.len());
+    let deserialize_arms = cases.iter().zip(field_keys).map(|(case, field_key)| {
+        let rust_name = &case.rust_name;
+
+        match &case.value_type {
+            Some(value_type) => quote! {
+                (#field_key, variant) => {
+                    serde::de::VariantAccess::newtype_variant::<#value_type>(variant)
+                        .map(#variant_ident::#rust_name)
+                }
+            },
+            None => quote! {
+                (#field_key, variant) => {
+                    serde::de::VariantAccess::unit_variant(variant)?;
+                    Ok(#variant_ident::#rust_name)
+                }
+            },
+        }
+    });
+    let visit = quote! {
+        fn visit_enum<A>(self, data: A) -> Result<Self::Value, A::Error>
+        where
+            A: serde::de::EnumAccess<'de>,
+        {
+            match serde::de::EnumAccess::variant::<_CdkFieldKey>(data)? {
+                #(#deserialize_arms)*
+                (_CdkFieldKey::Ignored, _) => {
+                    Err(serde::de::Error::custom("unknown variant case"))
+                }
+            }
+        }
+    };
+    let variant_name = variant_ident.to_string();
+    let case_names = cases.iter().map(|case| case.rust_name.to_string());
+    let deserialize = quote! {
+        serde::Deserializer::deserialize_enum(
+            deserializer,
+            #variant_name,
+            &[#(#case_names),*],
+            visitor
+        )
+    };
+
+    generate_impls(
+        variant_ident,
+        type_params,
+        &cases,
+        candid_type,
+        idl_serialize,
+        visit,
+        deserialize,
+    )
+}
+
+/// The members in the order of their label ids, which is the order candid
+/// expects the fields of a type and their values in
+fn to_sorted_fields(
+    context: &Context,
+    members: &[Member],
+    to_value_type: impl Fn(&Member) -> Option<TokenStream>,
+) -> Vec<Field> {
+    let mut fields: Vec<_> = members
+        .iter()
+        .map(|member| {
+            let (rust_name, _) = member.to_rust_name_and_rename_attribute(context);
+            let value_type = to_value_type(member);
+
+            match member.label_id {
+                Some(id) => Field {
+                    id,
+                    label: quote!(candid::types::Label::Id(#id)),
+                    // Records give the id of a field to the key visitor as a
+                    // number, variants give the id of a case as text
+                    name: id.to_string(),
+                    rust_name,
+                    value_type,
+                },
+                None => {
+                    let name = &member.name;
+
+                    Field {
+                        id: label::hash_label(name),
+                        label: quote!(candid::types::Label::Named(#name.to_string())),
+                        name: name.clone(),
+                        rust_name,
+                        value_type,
+                    }
+                }
+            }
+        })
+        .collect();
+
+    fields.sort_by_key(|field| field.id);
+    fields
+}
+
+fn generate_type(context: &Context, kind: &str, fields: &[Field]) -> TokenStream {
+    let fields: Vec<_> = fields
+        .iter()
+        .map(|field| {
+            let label = &field.label;
+            let value_type = match &field.value_type {
+                Some(value_type) => value_type.clone(),
+                None => quote!(()),
+            };
+
+            quote! {
+                candid::types::Field {
+                    id: #label.into(),
+                    ty: <#value_type as candid::CandidType>::ty(),
+                }
+            }
+        })
+        .collect();
+
+    context.target_version.generate_compound_type(kind, &fields)
+}
+
+fn generate_field_keys(len: usize) -> Vec<TokenStream> {
+    (0..len)
+        .map(|index| {
+            let key = format_ident!("Field{}", index);
+            quote!(_CdkFieldKey::#key)
+        })
+        .collect()
+}
+
+fn generate_impls(
+    ident: &Ident,
+    type_params: &TypeParams,
+    fields: &[Field],
+    candid_type: TokenStream,
+    idl_serialize: TokenStream,
+    visit: TokenStream,
+    deserialize: TokenStream,
+) -> TokenStream {
+    let type_params_token_stream = type_params.get_type_params_token_stream();
+    let type_param_idents: Vec<_> = type_params
+        .iter()
+        .map(|type_param| type_param.name.to_ident())
+        .collect();
+    let candid_where_clause = generate_where_clause(type_params, quote!(candid::CandidType));
+    let serde_where_clause = generate_where_clause(type_params, quote!(serde::Deserialize<'de>));
+
+    let field_keys = generate_field_keys(fields.len());
+    let key_variants = (0..fields.len()).map(|index| format_ident!("Field{}", index));
+    let id_arms = fields.iter().zip(&field_keys).map(|(field, field_key)| {
+        let id = Literal::u64_unsuffixed(field.id as u64);
+        quote!(#id => #field_key,)
+    });
+    let name_arms = fields.iter().zip(&field_keys).map(|(field, field_key)| {
+        let name = &field.name;
+        quote!(#name => #field_key,)
+    });
+
+    quote! {
+        impl #type_params_token_stream candid::CandidType for #ident #type_params_token_stream #candid_where_clause {
+            fn _ty() -> candid::types::Type {
+                #candid_type
+            }
+
+            fn id() -> candid::types::TypeId {
+                candid::types::TypeId::of::<Self>()
+            }
+
+            fn idl_serialize<S>(&self, serializer: S) -> Result<(), S::Error>
+            where
+                S: candid::types::Serializer,
+            {
+                #idl_serialize
+            }
+        }
+
+        impl<'de #(, #type_param_idents)*> serde::Deserialize<'de> for #ident #type_params_token_stream #serde_where_clause {
+            fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+            where
+                D: serde::Deserializer<'de>,
+            {
+                enum _CdkFieldKey {
+                    #(#key_variants,)*
+                    Ignored,
+                }
+
+                struct _CdkFieldKeyVisitor;
+
+                impl<'de> serde::de::Visitor<'de> for _CdkFieldKeyVisitor {
+                    type Value = _CdkFieldKey;
+
+                    fn expecting(&self, formatter: &mut std::fmt::Formatter) -> std::fmt::Result {
+                        formatter.write_str("a candid label")
+                    }
+
+                    fn visit_u64<E>(self, value: u64) -> Result<Self::Value, E>
+                    where
+                        E: serde::de::Error,
+                    {
+                        Ok(match value {
+                            #(#id_arms)*
+                            _ => _CdkFieldKey::Ignored,
+                        })
+                    }
+
+                    fn visit_str<E>(self, value: &str) -> Result<Self::Value, E>
+                    where
+                        E: serde::de::Error,
+                    {
+                        Ok(match value {
+                            #(#name_arms)*
+                            _ => _CdkFieldKey::Ignored,
+                        })
+                    }
+                }
+
+                impl<'de> serde::Deserialize<'de> for _CdkFieldKey {
+                    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+                    where
+                        D: serde::Deserializer<'de>,
+                    {
+                        serde::Deserializer::deserialize_identifier(deserializer, _CdkFieldKeyVisitor)
+                    }
+                }
+
+                struct _CdkVisitor<'de #(, #type_param_idents)*> #serde_where_clause {
+                    marker: std::marker::PhantomData<#ident #type_params_token_stream>,
+                    lifetime: std::marker::PhantomData<&'de ()>,
+                }
+
+                impl<'de #(, #type_param_idents)*> serde::de::Visitor<'de> for _CdkVisitor<'de #(, #type_param_idents)*> #serde_where_clause {
+                    type Value = #ident #type_params_token_stream;
+
+                    fn expecting(&self, formatter: &mut std::fmt::Formatter) -> std::fmt::Result {
+                        formatter.write_str(stringify!(#ident))
+                    }
+
+                    #visit
+                }
+
+                let visitor = _CdkVisitor {
+                    marker: std::marker::PhantomData,
+                    lifetime: std::marker::PhantomData,
+                };
+
+                #deserialize
+            }
+        }
+    }
+}
+
+/// The where clause of the type with the bound added to every type param
+fn generate_where_clause(type_params: &TypeParams, bound: TokenStream) -> TokenStream {
+    if type_params.is_empty() {
+        return quote!();
+    }
+
+    let where_clause_token_stream = type_params.get_where_clause_token_stream();
+    let bounds = type_params.iter().map(|type_param| {
+        let name = type_param.name.to_ident();
+        quote!(#name: #bound)
+    });
+
+    quote!(#where_clause_token_stream, #(#bounds),*)
+}
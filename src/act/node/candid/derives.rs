@@ -0,0 +1,75 @@
+use proc_macro2::TokenStream;
+use quote::quote;
+
+use crate::act::node::Context;
+
+/// The derives that are put on every generated record, variant, and tuple
+/// when the CDK doesn't provide its own default set
+pub fn default_derives() -> Vec<TokenStream> {
+    vec![
+        quote!(serde::Deserialize),
+        quote!(Debug),
+        quote!(candid::CandidType),
+        quote!(Clone),
+        quote!(CdkActTryIntoVmValue),
+        quote!(CdkActTryFromVmValue),
+        quote!(Ord),
+        quote!(PartialOrd),
+        quote!(Eq),
+        quote!(PartialEq),
+    ]
+}
+
+/// Combines the context's default derives and attributes with the ones
+/// declared on a specific type. Derives are deduplicated so that a type may
+/// list a derive that is already part of the default set. Attributes are
+/// complete outer attributes (e.g. `#[serde(rename_all = "camelCase")]`) and
+/// are emitted after the derive so that derive helper attributes resolve.
+/// The vm value conversion derives are left out when the conversions are
+/// generated from the VM adapter, and the candid and serde derives are left
+/// out for types with hand-written candid impls.
+pub fn generate_derives_and_attributes(
+    context: &Context,
+    derives: &[TokenStream],
+    attributes: &[TokenStream],
+    has_hand_written_candid_impls: bool,
+) -> TokenStream {
+    let vm_value_conversion_derives = [
+        quote!(CdkActTryIntoVmValue).to_string(),
+        quote!(CdkActTryFromVmValue).to_string(),
+    ];
+    let candid_derives = [
+        quote!(candid::CandidType).to_string(),
+        quote!(serde::Deserialize).to_string(),
+    ];
+    let derives = context.default_derives.iter().chain(derives).fold(
+        vec![],
+        |mut acc: Vec<&TokenStream>, derive| {
+            let is_generated_by_vm_adapter =
+                context.vm_adapter && vm_value_conversion_derives.contains(&derive.to_string());
+            let is_hand_written =
+                has_hand_written_candid_impls && candid_derives.contains(&derive.to_string());
+
+            if !is_generated_by_vm_adapter
+                && !is_hand_written
+                && !acc
+                    .iter()
+                    .any(|existing| existing.to_string() == derive.to_string())
+            {
+                acc.push(derive);
+            }
+            acc
+        },
+    );
+    let derive_attribute = if derives.is_empty() {
+        quote!()
+    } else {
+        quote!(#[derive(#(#derives),*)])
+    };
+    let attributes = context.default_attributes.iter().chain(attributes);
+
+    quote! {
+        #derive_attribute
+        #(#attributes)*
+    }
+}
@@ -4,9 +4,12 @@ use quote::{quote, ToTokens};
 use crate::{
     act::{
         node::{canister_method, node_parts::mode::Mode, CandidType, Context, Param, ReturnType},
+        target_version::Payment,
+        vm_value_conversion::vm_adapter,
         Declaration, Declare, ToTypeAnnotation, TypeAnnotation,
     },
-    traits::{HasInlines, HasTypeRefs, IsCallable, ToIdent},
+    keyword,
+    traits::{HasInlineName, HasInlines, HasTypeRefs, IsCallable, ToIdent},
     utils,
 };
 
@@ -53,24 +56,130 @@ impl Func {
             None => utils::create_inline_name(&inline_name),
         }
     }
+
+    fn get_rust_name(&self, inline_name: String) -> String {
+        keyword::make_type_name_rust_safe(&self.get_name(inline_name))
+    }
+
+    /// Generates methods on the func type that call the method a func value
+    /// references. Oneway funcs get `notify` and `notify_with_payment128`,
+    /// query funcs get `call`, and update funcs get `call` and
+    /// `call_with_payment128`.
+    fn generate_call_helpers(&self, context: &Context, inline_name: &String) -> TokenStream {
+        let param_types: Vec<_> = self
+            .get_params()
+            .iter()
+            .map(|param| {
+                param
+                    .candid_type
+                    .to_type_annotation(context, param.get_inline_name(inline_name))
+            })
+            .collect();
+        let params_tuple = quote!((#(#param_types,)*));
+
+        let return_type = self
+            .return_type
+            .to_type_annotation(context, inline_name.clone());
+        let reply_type = if return_type.to_string() == "" {
+            quote!(())
+        } else {
+            quote!((#return_type,))
+        };
+
+        let target_version = context.target_version;
+        let principal = quote!(self.0.principal);
+        let method = quote!(&self.0.method);
+        let notify_result_type = target_version.generate_notify_result_type();
+        let call_result_type = target_version.generate_call_result_type(&reply_type);
+        let cycles = Payment::Cycles128(quote!(cycles));
+
+        match self.mode {
+            Mode::Oneway => {
+                let notify = target_version.generate_notify(
+                    principal.clone(),
+                    method.clone(),
+                    quote!(params),
+                    &Payment::None,
+                );
+                let notify_with_payment128 =
+                    target_version.generate_notify(principal, method, quote!(params), &cycles);
+
+                quote! {
+                    pub fn notify(&self, params: #params_tuple) -> #notify_result_type {
+                        #notify
+                    }
+
+                    pub fn notify_with_payment128(
+                        &self,
+                        params: #params_tuple,
+                        cycles: u128,
+                    ) -> #notify_result_type {
+                        #notify_with_payment128
+                    }
+                }
+            }
+            Mode::Query | Mode::CompositeQuery => {
+                let call =
+                    target_version.generate_call(principal, method, quote!(params), &Payment::None);
+
+                quote! {
+                    pub async fn call(&self, params: #params_tuple) -> #call_result_type {
+                        #call
+                    }
+                }
+            }
+            Mode::Update => {
+                let call = target_version.generate_call(
+                    principal.clone(),
+                    method.clone(),
+                    quote!(params),
+                    &Payment::None,
+                );
+                let call_with_payment128 =
+                    target_version.generate_call(principal, method, quote!(params), &cycles);
+
+                quote! {
+                    pub async fn call(&self, params: #params_tuple) -> #call_result_type {
+                        #call
+                    }
+
+                    pub async fn call_with_payment128(
+                        &self,
+                        params: #params_tuple,
+                        cycles: u128,
+                    ) -> #call_result_type {
+                        #call_with_payment128
+                    }
+                }
+            }
+        }
+    }
 }
 
 impl<C> ToTypeAnnotation<C> for Func {
     fn to_type_annotation(&self, _: &C, inline_name: String) -> TypeAnnotation {
-        self.get_name(inline_name).to_ident().to_token_stream()
+        self.get_rust_name(inline_name).to_ident().to_token_stream()
     }
 }
 
 impl Declare<Context> for Func {
     fn to_declaration(&self, context: &Context, inline_name: String) -> Option<Declaration> {
-        let name = self.get_name(inline_name.clone()).to_ident();
+        let name = self.get_rust_name(inline_name.clone()).to_ident();
         let func_macro_token_stream =
             self.get_func_macro_token_stream(&inline_name, context, &self.mode);
 
-        let func_to_vm_value = (self.to_vm_value)(self.get_name(inline_name.clone()));
-        let func_list_to_vm_value = (self.list_to_vm_value)(self.get_name(inline_name.clone()));
-        let func_from_vm_value = (self.from_vm_value)(self.get_name(inline_name.clone()));
-        let func_list_from_vm_value = (self.list_from_vm_value)(self.get_name(inline_name.clone()));
+        let func_to_vm_value = (self.to_vm_value)(self.get_rust_name(inline_name.clone()));
+        let func_list_to_vm_value =
+            (self.list_to_vm_value)(self.get_rust_name(inline_name.clone()));
+        let func_from_vm_value = (self.from_vm_value)(self.get_rust_name(inline_name.clone()));
+        let func_list_from_vm_value =
+            (self.list_from_vm_value)(self.get_rust_name(inline_name.clone()));
+        let call_helpers = self.generate_call_helpers(context, &inline_name);
+        let vm_adapter_impls = if context.vm_adapter {
+            vm_adapter::generate_func_impls(&name)
+        } else {
+            quote!()
+        };
 
         Some(quote! {
             candid::define_function!(pub #name : #func_macro_token_stream);
@@ -80,15 +189,28 @@ impl Declare<Context> for Func {
             #func_from_vm_value
             #func_list_from_vm_value
 
+            impl #name {
+                #call_helpers
+            }
+
+            #vm_adapter_impls
+
             impl std::cmp::Ord for #name {
                 fn cmp(&self, other: &Self) -> std::cmp::Ordering {
-                    self.partial_cmp(other).unwrap_or(std::cmp::Ordering::Equal)
+                    (&self.0.principal, &self.0.method).cmp(&(&other.0.principal, &other.0.method))
                 }
             }
 
             impl std::cmp::PartialOrd for #name {
                 fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
-                    None
+                    Some(self.cmp(other))
+                }
+            }
+
+            impl std::hash::Hash for #name {
+                fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
+                    std::hash::Hash::hash(&self.0.principal, state);
+                    std::hash::Hash::hash(&self.0.method, state);
                 }
             }
         })
@@ -107,6 +229,7 @@ fn to_param(index: usize, candid_type: &CandidType) -> Param {
     Param {
         name: format!("Param{}", index.to_string()),
         candid_type: candid_type.clone(),
+        docs: None,
     }
 }
 
@@ -4,6 +4,8 @@ use crate::{
 };
 
 pub mod array;
+pub mod candid_impls;
+pub mod derives;
 pub mod func;
 pub mod opt;
 pub mod primitive;
@@ -1,23 +1,28 @@
 use proc_macro2::TokenStream;
 use quote::quote;
 
-use crate::{
-    act::{node::Context, ToTypeAnnotation},
-    keyword,
-    traits::ToIdent,
-};
+use crate::act::{docs, node::Context, ToTypeAnnotation};
 
 use super::Member;
 
 impl Member {
+    /// The field of the generated struct. The serde rename attribute is left
+    /// out for records with hand-written candid impls, which have no serde
+    /// derive to read it.
     pub fn to_record_member_token_stream(
         &self,
         context: &Context,
         parent_name: String,
+        with_rename_attribute: bool,
     ) -> TokenStream {
         let type_annotation = self.to_type_annotation(context, parent_name);
-        let name = keyword::make_rust_safe(&self.name, &context.keyword_list).to_ident();
-        let rename_attr = keyword::generate_rename_attribute(&name, &context.keyword_list);
-        quote!(#rename_attr #name: Box<#type_annotation>)
+        let (name, rename_attr) = self.to_rust_name_and_rename_attribute(context);
+        let rename_attr = if with_rename_attribute {
+            rename_attr
+        } else {
+            quote!()
+        };
+        let doc_attributes = docs::generate_doc_attributes(&self.docs);
+        quote!(#doc_attributes #rename_attr #name: Box<#type_annotation>)
     }
 }
@@ -1,10 +1,17 @@
+use proc_macro2::TokenStream;
 use quote::{quote, ToTokens};
 
 use crate::{
     act::{
-        node::{candid::type_param::TypeParams, Context},
+        docs,
+        node::{
+            candid::{candid_impls, derives, type_param::TypeParams},
+            Context,
+        },
+        vm_value_conversion::vm_adapter,
         Declaration, Declare, ToTypeAnnotation, TypeAnnotation,
     },
+    keyword,
     traits::{HasInlines, HasMembers, ToIdent},
     utils,
 };
@@ -16,6 +23,9 @@ pub struct Record {
     pub name: Option<String>,
     pub members: Vec<Member>,
     pub type_params: TypeParams,
+    pub derives: Vec<TokenStream>,
+    pub attributes: Vec<TokenStream>,
+    pub docs: Option<String>,
 }
 
 impl Record {
@@ -25,32 +35,77 @@ impl Record {
             None => utils::create_inline_name(inline_name),
         }
     }
+
+    fn get_rust_name(&self, inline_name: &String) -> String {
+        keyword::make_type_name_rust_safe(&self.get_name(inline_name))
+    }
 }
 
 impl ToTypeAnnotation<Context> for Record {
     fn to_type_annotation(&self, _: &Context, inline_name: String) -> TypeAnnotation {
-        self.get_name(&inline_name).to_ident().to_token_stream()
+        self.get_rust_name(&inline_name)
+            .to_ident()
+            .to_token_stream()
     }
 }
 
 impl Declare<Context> for Record {
     fn to_declaration(&self, context: &Context, inline_name: String) -> Option<Declaration> {
-        let record_ident = self.get_name(&inline_name).to_ident();
+        let record_ident = self.get_rust_name(&inline_name).to_ident();
+        let has_label_ids = candid_impls::has_label_ids(&self.members);
         let member_token_streams: Vec<_> = self
             .members
             .iter()
             .map(|member| {
-                member.to_record_member_token_stream(context, self.get_name(&inline_name))
+                member.to_record_member_token_stream(
+                    context,
+                    self.get_name(&inline_name),
+                    !has_label_ids,
+                )
             })
             .collect();
         let type_params_token_stream = self.type_params.get_type_params_token_stream();
         let where_clause_token_stream = self.type_params.get_where_clause_token_stream();
+        let derives_and_attributes = derives::generate_derives_and_attributes(
+            context,
+            &self.derives,
+            &self.attributes,
+            has_label_ids,
+        );
+        let doc_attributes = docs::generate_doc_attributes(&self.docs);
+        let vm_adapter_impls = if context.vm_adapter {
+            let fields: Vec<_> = self
+                .members
+                .iter()
+                .map(|member| member.to_rust_and_vm_names(context))
+                .collect();
+
+            vm_adapter::generate_record_impls(&record_ident, &self.type_params, &fields)
+        } else {
+            quote!()
+        };
+
+        let candid_impls = if has_label_ids {
+            candid_impls::generate_record_impls(
+                context,
+                &record_ident,
+                &self.type_params,
+                &self.members,
+                &self.get_name(&inline_name),
+            )
+        } else {
+            quote!()
+        };
 
         Some(quote!(
-            #[derive(serde::Deserialize, Debug, candid::CandidType, Clone, CdkActTryIntoVmValue, CdkActTryFromVmValue, Ord, PartialOrd, Eq, PartialEq)]
+            #doc_attributes
+            #derives_and_attributes
             struct #record_ident #type_params_token_stream #where_clause_token_stream {
                 #(#member_token_streams),*
             }
+
+            #candid_impls
+            #vm_adapter_impls
         ))
     }
 
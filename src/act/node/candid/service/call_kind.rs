@@ -0,0 +1,106 @@
+use proc_macro2::TokenStream;
+use quote::quote;
+
+use crate::act::target_version::Payment;
+
+/// The kinds of cross canister call functions generated for a service
+/// method. Each kind generates one function named
+/// `{prefix}_{canister}_{method}`, for example `call_ledger_transfer`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum CallKind {
+    /// `call_*`, a call without cycles
+    Call,
+    /// `call_with_payment_*`, a call with `u64` cycles
+    CallWithPayment,
+    /// `call_with_payment128_*`, a call with `u128` cycles
+    CallWithPayment128,
+    /// `notify_*`, a oneway call that doesn't wait for a reply
+    Notify,
+    /// `notify_with_payment128_*`, a oneway call with `u128` cycles
+    NotifyWithPayment128,
+    /// `bounded_wait_*`, a call with `u128` cycles whose caller gets an
+    /// answer within `timeout_seconds` even if the callee never replies.
+    /// Best-effort responses need the `ic_cdk::call::Call` API, so this
+    /// kind needs [TargetVersion::IcCdk0_18](crate::act::target_version::TargetVersion).
+    BoundedWait { timeout_seconds: u32 },
+    /// `bounded_wait_raw_*`, like [CallKind::BoundedWait] with already
+    /// encoded args and the undecoded reply
+    BoundedWaitRaw { timeout_seconds: u32 },
+    /// `call_raw_*`, a call with already encoded args and `u64` cycles that
+    /// returns the undecoded reply
+    CallRaw,
+    /// `call_raw128_*`, like [CallKind::CallRaw] with `u128` cycles
+    CallRaw128,
+}
+
+impl CallKind {
+    /// The kinds that are generated when neither the service nor the method
+    /// selects any
+    pub fn defaults() -> Vec<CallKind> {
+        vec![
+            CallKind::Call,
+            CallKind::CallWithPayment,
+            CallKind::CallWithPayment128,
+            CallKind::Notify,
+            CallKind::NotifyWithPayment128,
+        ]
+    }
+
+    pub fn get_function_prefix(&self) -> &'static str {
+        match self {
+            CallKind::Call => "call",
+            CallKind::CallWithPayment => "call_with_payment",
+            CallKind::CallWithPayment128 => "call_with_payment128",
+            CallKind::Notify => "notify",
+            CallKind::NotifyWithPayment128 => "notify_with_payment128",
+            CallKind::BoundedWait { .. } => "bounded_wait",
+            CallKind::BoundedWaitRaw { .. } => "bounded_wait_raw",
+            CallKind::CallRaw => "call_raw",
+            CallKind::CallRaw128 => "call_raw128",
+        }
+    }
+
+    pub fn is_oneway(&self) -> bool {
+        matches!(self, CallKind::Notify | CallKind::NotifyWithPayment128)
+    }
+
+    pub fn is_raw(&self) -> bool {
+        matches!(
+            self,
+            CallKind::CallRaw | CallKind::CallRaw128 | CallKind::BoundedWaitRaw { .. }
+        )
+    }
+
+    pub fn is_bounded_wait(&self) -> bool {
+        matches!(
+            self,
+            CallKind::BoundedWait { .. } | CallKind::BoundedWaitRaw { .. }
+        )
+    }
+
+    /// The type of the `cycles` param, if the function has one
+    pub fn get_cycles_type(&self) -> Option<TokenStream> {
+        match self {
+            CallKind::Call | CallKind::Notify => None,
+            CallKind::CallWithPayment | CallKind::CallRaw => Some(quote!(u64)),
+            CallKind::CallWithPayment128
+            | CallKind::NotifyWithPayment128
+            | CallKind::BoundedWait { .. }
+            | CallKind::BoundedWaitRaw { .. }
+            | CallKind::CallRaw128 => Some(quote!(u128)),
+        }
+    }
+
+    /// The `cycles` param as the payment of the call
+    pub fn get_payment(&self) -> Payment {
+        match self {
+            CallKind::Call | CallKind::Notify => Payment::None,
+            CallKind::CallWithPayment | CallKind::CallRaw => Payment::Cycles(quote!(cycles)),
+            CallKind::CallWithPayment128
+            | CallKind::NotifyWithPayment128
+            | CallKind::BoundedWait { .. }
+            | CallKind::BoundedWaitRaw { .. }
+            | CallKind::CallRaw128 => Payment::Cycles128(quote!(cycles)),
+        }
+    }
+}
@@ -0,0 +1,134 @@
+use proc_macro2::TokenStream;
+use quote::{format_ident, quote};
+
+use super::Service;
+use crate::{
+    act::{node::Context, target_version::Payment},
+    keyword,
+    traits::ToIdent,
+};
+
+/// Generates a `<service>Client` trait with an async method per service
+/// method, so that code calling the service can be written against the trait
+/// and tested off-chain. The trait is implemented by
+/// - the service type itself, which calls the canister it references
+/// - a `<service>Mock`, only on native targets, which records the params of
+///   every call in `<method>_calls` and answers with the responses queued in
+///   `<method>_responses`. Calls without a queued response are rejected, or
+///   panic with ic-cdk 0.18.
+pub fn generate_client(service: &Service, context: &Context) -> TokenStream {
+    let service_name = format_ident!("{}", service.get_rust_name());
+    let client_name = format_ident!("{}Client", service.get_rust_name());
+    let mock_name = format_ident!("{}Mock", service.get_rust_name());
+    let target_version = context.target_version;
+
+    let methods: Vec<_> = service
+        .methods
+        .iter()
+        .map(|method| {
+            let method_ident =
+                keyword::make_rust_safe(&method.name, &context.keyword_list).to_ident();
+            let param_types = method.param_types_as_tuple(context, &service.name);
+            let reply_type = method.reply_type_as_tuple(context, &service.name);
+            let call_result_type = target_version.generate_call_result_type(&reply_type);
+            let return_type = quote! {
+                std::pin::Pin<Box<dyn std::future::Future<Output = #call_result_type> + '_>>
+            };
+
+            (
+                method,
+                method_ident,
+                param_types,
+                call_result_type,
+                return_type,
+            )
+        })
+        .collect();
+
+    let trait_methods = methods
+        .iter()
+        .map(|(_, method_ident, param_types, _, return_type)| {
+            quote! {
+                fn #method_ident(&self, params: #param_types) -> #return_type;
+            }
+        });
+
+    let canister_methods =
+        methods
+            .iter()
+            .map(|(method, method_ident, param_types, _, return_type)| {
+                let method_name = &method.name;
+                let call = target_version.generate_call(
+                    quote!(self.0.principal),
+                    quote!(#method_name),
+                    quote!(params),
+                    &Payment::None,
+                );
+
+                quote! {
+                    fn #method_ident(&self, params: #param_types) -> #return_type {
+                        Box::pin(async move { #call })
+                    }
+                }
+            });
+
+    let mock_fields = methods
+        .iter()
+        .map(|(_, method_ident, param_types, call_result_type, _)| {
+            let calls_field = format_ident!("{}_calls", method_ident);
+            let responses_field = format_ident!("{}_responses", method_ident);
+
+            quote! {
+                #calls_field: std::cell::RefCell<Vec<#param_types>>,
+                #responses_field: std::cell::RefCell<std::collections::VecDeque<#call_result_type>>,
+            }
+        });
+
+    let mock_methods = methods
+        .iter()
+        .map(|(method, method_ident, param_types, _, return_type)| {
+            let method_name = &method.name;
+            let calls_field = format_ident!("{}_calls", method_ident);
+            let responses_field = format_ident!("{}_responses", method_ident);
+            let failure = target_version.generate_mock_call_failure(quote!(
+                format!("No mock response for {}", #method_name)
+            ));
+
+            quote! {
+                fn #method_ident(&self, params: #param_types) -> #return_type {
+                    self.#calls_field.borrow_mut().push(params);
+
+                    let response = self
+                        .#responses_field
+                        .borrow_mut()
+                        .pop_front()
+                        .unwrap_or_else(|| #failure);
+
+                    Box::pin(async move { response })
+                }
+            }
+        });
+
+    quote! {
+        #[allow(non_camel_case_types)]
+        trait #client_name {
+            #(#trait_methods)*
+        }
+
+        impl #client_name for #service_name {
+            #(#canister_methods)*
+        }
+
+        #[cfg(not(target_arch = "wasm32"))]
+        #[allow(non_camel_case_types)]
+        #[derive(Default)]
+        struct #mock_name {
+            #(#mock_fields)*
+        }
+
+        #[cfg(not(target_arch = "wasm32"))]
+        impl #client_name for #mock_name {
+            #(#mock_methods)*
+        }
+    }
+}
@@ -1,8 +1,10 @@
 use proc_macro2::TokenStream;
 use quote::{format_ident, quote};
 
+use super::CallKind;
 use crate::{
     act::{
+        docs,
         node::{
             candid::TypeRef, canister_method, node_parts::mode::Mode, CandidType, Context, Param,
             ReturnType,
@@ -18,6 +20,10 @@ pub struct Method {
     pub mode: Mode,
     pub params: Vec<Param>,
     pub return_type: ReturnType,
+    pub docs: Option<String>,
+    /// The call functions generated for this method. `None` uses the
+    /// service's. See [CallKind].
+    pub call_kinds: Option<Vec<CallKind>>,
 }
 
 impl Method {
@@ -27,6 +33,8 @@ impl Method {
             mode,
             params,
             return_type: ReturnType::new(return_type),
+            docs: None,
+            call_kinds: None,
         }
     }
 
@@ -38,80 +46,146 @@ impl Method {
         )
     }
 
-    fn generate_call_function(
+    /// The method's call kinds, or else the service's, or else
+    /// [CallKind::defaults]
+    pub fn get_call_kinds(&self, service_call_kinds: &Option<Vec<CallKind>>) -> Vec<CallKind> {
+        self.call_kinds
+            .clone()
+            .or_else(|| service_call_kinds.clone())
+            .unwrap_or_else(CallKind::defaults)
+    }
+
+    pub fn generate_call_functions(
         &self,
         canister_name: &String,
-        function_type: &str,
+        call_kinds: &[CallKind],
         context: &Context,
     ) -> TokenStream {
-        let is_oneway = function_type.contains("notify");
-
-        let async_or_not = if is_oneway {
-            quote! {}
-        } else {
-            quote! {async}
-        };
-
-        let function_name = format_ident!("{}_{}_{}", function_type, canister_name, &self.name);
+        let call_functions = call_kinds
+            .iter()
+            .map(|call_kind| self.generate_call_function(canister_name, call_kind, context));
 
-        let param_types = self.param_types_as_tuple(context, canister_name);
+        quote!(#(#call_functions)*)
+    }
 
-        let cycles_param = if function_type.contains("with_payment128") {
-            quote! { , cycles: u128 }
-        } else if function_type.contains("with_payment") {
-            quote! { , cycles: u64 }
+    fn generate_call_function(
+        &self,
+        canister_name: &String,
+        call_kind: &CallKind,
+        context: &Context,
+    ) -> TokenStream {
+        let function_name = format_ident!(
+            "{}_{}_{}",
+            call_kind.get_function_prefix(),
+            canister_name,
+            &self.name
+        );
+
+        let param_types = if call_kind.is_raw() {
+            quote! { Vec<u8> }
         } else {
-            quote! {}
+            self.param_types_as_tuple(context, canister_name)
         };
 
-        let function_return_type = self
-            .return_type
-            .to_type_annotation(context, self.create_qualified_name(canister_name));
-        let return_type = if is_oneway {
-            quote! {Result<(), ic_cdk::api::call::RejectionCode>}
-        } else {
-            if function_return_type.to_string() == "" {
-                quote! {ic_cdk::api::call::CallResult<()>}
-            } else {
-                quote! {ic_cdk::api::call::CallResult<(#function_return_type,)>}
-            }
+        let cycles_param = match call_kind.get_cycles_type() {
+            Some(cycles_type) => quote! { , cycles: #cycles_type },
+            None => quote! {},
         };
 
-        let function_type_ident = format_ident!("{}", function_type);
-        let api_call = quote! { ic_cdk::api::call::#function_type_ident };
+        let reply_type = self.reply_type_as_tuple(context, canister_name);
 
         let method_name = &self.name;
 
-        let cycles_arg = if function_type.contains("with_payment") {
-            quote! { , cycles }
-        } else {
-            quote! {}
-        };
-
-        let await_or_not = if is_oneway {
-            quote! {}
-        } else {
-            quote! {.await}
+        let doc_attributes = docs::generate_callable_doc_attributes(&self.docs, &self.params);
+
+        let target_version = context.target_version;
+        let payment = call_kind.get_payment();
+
+        let (async_or_not, return_type, function_body) = match call_kind {
+            CallKind::Notify | CallKind::NotifyWithPayment128 => (
+                quote! {},
+                target_version.generate_notify_result_type(),
+                target_version.generate_notify(
+                    quote!(canister_id_principal),
+                    quote!(#method_name),
+                    quote!(params),
+                    &payment,
+                ),
+            ),
+            CallKind::BoundedWait { timeout_seconds } => (
+                quote! {async},
+                quote! {Result<#reply_type, ic_cdk::call::Error>},
+                quote! {
+                    let response = ic_cdk::call::Call::bounded_wait(canister_id_principal, #method_name)
+                        .change_timeout(#timeout_seconds)
+                        .with_args(&params)
+                        .with_cycles(cycles)
+                        .await?;
+
+                    Ok(response.candid_tuple()?)
+                },
+            ),
+            CallKind::BoundedWaitRaw { timeout_seconds } => (
+                quote! {async},
+                quote! {Result<Vec<u8>, ic_cdk::call::Error>},
+                quote! {
+                    ic_cdk::call::Call::bounded_wait(canister_id_principal, #method_name)
+                        .change_timeout(#timeout_seconds)
+                        .with_raw_args(&params)
+                        .with_cycles(cycles)
+                        .await
+                        .map(|response| response.into_bytes())
+                        .map_err(ic_cdk::call::Error::from)
+                },
+            ),
+            CallKind::CallRaw | CallKind::CallRaw128 => (
+                quote! {async},
+                target_version.generate_call_result_type(&quote!(Vec<u8>)),
+                target_version.generate_call_raw(
+                    quote!(canister_id_principal),
+                    quote!(#method_name),
+                    quote!(params),
+                    &payment,
+                ),
+            ),
+            CallKind::Call | CallKind::CallWithPayment | CallKind::CallWithPayment128 => (
+                quote! {async},
+                target_version.generate_call_result_type(&reply_type),
+                target_version.generate_call(
+                    quote!(canister_id_principal),
+                    quote!(#method_name),
+                    quote!(params),
+                    &payment,
+                ),
+            ),
         };
 
         quote! {
+            #doc_attributes
             #[allow(non_snake_case)]
             #async_or_not fn #function_name(
                 canister_id_principal: candid::Principal,
                 params: #param_types
                 #cycles_param
             ) -> #return_type {
-                #api_call(
-                    canister_id_principal,
-                    #method_name,
-                    params
-                    #cycles_arg
-                )#await_or_not
+                #function_body
             }
         }
     }
 
-    fn param_types_as_tuple(&self, context: &Context, canister_name: &String) -> TokenStream {
+    pub fn reply_type_as_tuple(&self, context: &Context, canister_name: &String) -> TokenStream {
+        let return_type = self
+            .return_type
+            .to_type_annotation(context, self.create_qualified_name(canister_name));
+
+        if return_type.to_string() == "" {
+            quote! {()}
+        } else {
+            quote! {(#return_type,)}
+        }
+    }
+
+    pub fn param_types_as_tuple(&self, context: &Context, canister_name: &String) -> TokenStream {
         let param_types: Vec<_> = self
             .params
             .iter()
@@ -131,22 +205,7 @@ impl Method {
 
 impl Declare<Context> for Method {
     fn to_declaration(&self, context: &Context, canister_name: String) -> Option<Declaration> {
-        let call_function = self.generate_call_function(&canister_name, "call", &context);
-        let call_with_payment_function =
-            self.generate_call_function(&canister_name, "call_with_payment", &context);
-        let call_with_payment128_function =
-            self.generate_call_function(&canister_name, "call_with_payment128", &context);
-        let notify_function = self.generate_call_function(&canister_name, "notify", &context);
-        let notify_with_payment128_function =
-            self.generate_call_function(&canister_name, "notify_with_payment128", &context);
-
-        Some(quote! {
-            #call_function
-            #call_with_payment_function
-            #call_with_payment128_function
-            #notify_function
-            #notify_with_payment128_function
-        })
+        Some(self.generate_call_functions(&canister_name, &self.get_call_kinds(&None), context))
     }
 
     fn collect_inline_declarations(
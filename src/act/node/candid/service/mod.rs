@@ -1,5 +1,8 @@
+pub mod call_kind;
+pub mod client;
 pub mod method;
 pub mod service;
 
+pub use call_kind::CallKind;
 pub use method::Method;
 pub use service::Service;
@@ -1,12 +1,14 @@
 use proc_macro2::TokenStream;
 use quote::{quote, ToTokens};
 
-use super::Method;
+use super::{client, CallKind, Method};
 use crate::{
     act::{
         node::{candid::TypeRef, Context},
+        vm_value_conversion::vm_adapter,
         Declaration, Declare, TypeAnnotation,
     },
+    keyword,
     traits::{HasTypeRefs, IsCallable, ToIdent, ToTypeAnnotation},
 };
 
@@ -14,6 +16,12 @@ use crate::{
 pub struct Service {
     pub name: String,
     pub methods: Vec<Method>,
+    /// Rust doesn't allow doc comments on the `candid::define_service!`
+    /// invocation, so these docs only appear in the generated .did
+    pub docs: Option<String>,
+    /// The call functions generated for every method that doesn't select
+    /// its own. `None` uses [CallKind::defaults].
+    pub call_kinds: Option<Vec<CallKind>>,
     pub to_vm_value: fn(String) -> TokenStream,
     pub list_to_vm_value: fn(String) -> TokenStream,
     pub from_vm_value: fn(String) -> TokenStream,
@@ -21,14 +29,14 @@ pub struct Service {
 }
 
 impl Service {
-    fn get_name(&self, _: &String) -> String {
-        self.name.clone()
+    pub(crate) fn get_rust_name(&self) -> String {
+        keyword::make_type_name_rust_safe(&self.name)
     }
 }
 
 impl ToTypeAnnotation<Context> for Service {
-    fn to_type_annotation(&self, _: &Context, inline_name: String) -> TypeAnnotation {
-        self.get_name(&inline_name).to_ident().to_token_stream()
+    fn to_type_annotation(&self, _: &Context, _: String) -> TypeAnnotation {
+        self.get_rust_name().to_ident().to_token_stream()
     }
 }
 
@@ -37,10 +45,16 @@ impl Declare<Context> for Service {
         let cross_canister_call_functions: Vec<_> = self
             .methods
             .iter()
-            .filter_map(|method| method.to_declaration(context, self.name.clone()))
+            .map(|method| {
+                method.generate_call_functions(
+                    &self.name,
+                    &method.get_call_kinds(&self.call_kinds),
+                    context,
+                )
+            })
             .collect();
 
-        let service_name = self.name.to_ident();
+        let service_name = self.get_rust_name().to_ident();
         let service_funcs: Vec<_> = self
             .methods
             .iter()
@@ -58,10 +72,17 @@ impl Declare<Context> for Service {
             })
             .collect();
 
-        let service_to_vm_value = (self.to_vm_value)(self.name.clone());
-        let service_list_to_vm_value = (self.list_to_vm_value)(self.name.clone());
-        let service_from_vm_value = (self.from_vm_value)(self.name.clone());
-        let service_list_from_vm_value = (self.list_from_vm_value)(self.name.clone());
+        let client = client::generate_client(self, context);
+        let vm_adapter_impls = if context.vm_adapter {
+            vm_adapter::generate_service_impls(&service_name)
+        } else {
+            quote!()
+        };
+
+        let service_to_vm_value = (self.to_vm_value)(self.get_rust_name());
+        let service_list_to_vm_value = (self.list_to_vm_value)(self.get_rust_name());
+        let service_from_vm_value = (self.from_vm_value)(self.get_rust_name());
+        let service_list_from_vm_value = (self.list_from_vm_value)(self.get_rust_name());
 
         Some(quote! {
             candid::define_service!(#service_name : {
@@ -70,13 +91,19 @@ impl Declare<Context> for Service {
 
             impl std::cmp::Ord for #service_name {
                 fn cmp(&self, other: &Self) -> std::cmp::Ordering {
-                    self.partial_cmp(other).unwrap_or(std::cmp::Ordering::Equal)
+                    self.0.principal.cmp(&other.0.principal)
                 }
             }
 
             impl std::cmp::PartialOrd for #service_name {
                 fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
-                    None
+                    Some(self.cmp(other))
+                }
+            }
+
+            impl std::hash::Hash for #service_name {
+                fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
+                    std::hash::Hash::hash(&self.0.principal, state);
                 }
             }
 
@@ -84,8 +111,11 @@ impl Declare<Context> for Service {
             #service_list_to_vm_value
             #service_from_vm_value
             #service_list_from_vm_value
+            #vm_adapter_impls
 
             #(#cross_canister_call_functions)*
+
+            #client
         })
     }
 
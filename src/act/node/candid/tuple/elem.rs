@@ -30,6 +30,8 @@ impl Elem {
         Member {
             name: index.to_string(),
             candid_type: self.candid_type.clone(),
+            docs: None,
+            label_id: None,
         }
     }
 }
@@ -4,9 +4,15 @@ use quote::{quote, ToTokens};
 use super::Elem;
 use crate::{
     act::{
-        node::{candid::type_param::TypeParams, Context, Member},
+        docs,
+        node::{
+            candid::{derives, type_param::TypeParams},
+            Context, Member,
+        },
+        vm_value_conversion::vm_adapter,
         Declaration, Declare, ToTypeAnnotation, TypeAnnotation,
     },
+    keyword,
     traits::{HasInlines, HasMembers, ToIdent},
     utils,
 };
@@ -16,6 +22,9 @@ pub struct Tuple {
     pub name: Option<String>,
     pub elems: Vec<Elem>,
     pub type_params: TypeParams,
+    pub derives: Vec<TokenStream>,
+    pub attributes: Vec<TokenStream>,
+    pub docs: Option<String>,
 }
 
 impl Tuple {
@@ -25,17 +34,23 @@ impl Tuple {
             None => utils::create_inline_name(&inline_name),
         }
     }
+
+    fn get_rust_name(&self, inline_name: &String) -> String {
+        keyword::make_type_name_rust_safe(&self.get_name(inline_name))
+    }
 }
 
 impl<C> ToTypeAnnotation<C> for Tuple {
     fn to_type_annotation(&self, _: &C, inline_name: String) -> TypeAnnotation {
-        self.get_name(&inline_name).to_ident().to_token_stream()
+        self.get_rust_name(&inline_name)
+            .to_ident()
+            .to_token_stream()
     }
 }
 
 impl Declare<Context> for Tuple {
     fn to_declaration(&self, context: &Context, inline_name: String) -> Option<Declaration> {
-        let tuple_ident = self.get_name(&inline_name).to_ident();
+        let tuple_ident = self.get_rust_name(&inline_name).to_ident();
         let member_idents: Vec<TokenStream> = self
             .elems
             .iter()
@@ -54,12 +69,27 @@ impl Declare<Context> for Tuple {
 
         let type_params_token_stream = self.type_params.get_type_params_token_stream();
         let where_clause_token_stream = self.type_params.get_where_clause_token_stream();
+        let derives_and_attributes = derives::generate_derives_and_attributes(
+            context,
+            &self.derives,
+            &self.attributes,
+            false,
+        );
+        let doc_attributes = docs::generate_doc_attributes(&self.docs);
+        let vm_adapter_impls = if context.vm_adapter {
+            vm_adapter::generate_tuple_impls(&tuple_ident, &self.type_params, self.elems.len())
+        } else {
+            quote!()
+        };
 
         Some(quote!(
-            #[derive(serde::Deserialize, Debug, candid::CandidType, Clone, CdkActTryIntoVmValue, CdkActTryFromVmValue, Ord, PartialOrd, Eq, PartialEq)]
+            #doc_attributes
+            #derives_and_attributes
             struct #tuple_ident #type_params_token_stream (
                 #member_idents
             ) #where_clause_token_stream;
+
+            #vm_adapter_impls
         ))
     }
 
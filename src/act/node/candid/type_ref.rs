@@ -2,6 +2,7 @@ use quote::{quote, ToTokens};
 
 use crate::{
     act::{node::Context, Declaration, Declare, ToTypeAnnotation, TypeAnnotation},
+    keyword,
     traits::{HasTypeRefs, ToIdent, ToTokenStream},
 };
 
@@ -15,8 +16,9 @@ pub struct TypeRef {
 
 impl ToTypeAnnotation<Context> for TypeRef {
     fn to_type_annotation(&self, context: &Context, inline_name: String) -> TypeAnnotation {
-        // TODO use the keyword list to make the identifier rust safe
-        let name = self.name.to_ident().to_token_stream();
+        let name = keyword::make_type_name_rust_safe(&self.name)
+            .to_ident()
+            .to_token_stream();
         let type_arguments_token_stream =
             self.type_arguments.to_token_stream(context, &inline_name);
 
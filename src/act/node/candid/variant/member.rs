@@ -2,41 +2,66 @@ use proc_macro2::TokenStream;
 use quote::quote;
 
 use super::Member;
-use crate::{
-    act::{
-        node::{CandidType, Context},
-        ToTypeAnnotation,
-    },
-    keyword,
-    traits::ToIdent,
+use crate::act::{
+    docs,
+    node::{CandidType, Context},
+    ToTypeAnnotation,
 };
 
 impl Member {
+    /// The case of the generated enum. The serde rename attribute is left out
+    /// for variants with hand-written candid impls, which have no serde
+    /// derive to read it.
     pub fn to_variant_member_token_stream(
         &self,
         context: &Context,
         parent_name: String,
+        with_rename_attribute: bool,
     ) -> TokenStream {
-        let member_type_token_stream = match self.candid_type.clone() {
+        let member_type_token_stream = match self.to_variant_value_type(context, parent_name) {
+            Some(value_type) => quote!((#value_type)),
+            None => quote!(),
+        };
+        let (member_name, rename_attr) = self.to_rust_name_and_rename_attribute(context);
+        let rename_attr = if with_rename_attribute {
+            rename_attr
+        } else {
+            quote!()
+        };
+        let doc_attributes = docs::generate_doc_attributes(&self.docs);
+        quote! {#doc_attributes #rename_attr #member_name #member_type_token_stream}
+    }
+
+    /// The type of the value that the case of the generated enum holds, if any
+    pub fn to_variant_value_type(
+        &self,
+        context: &Context,
+        parent_name: String,
+    ) -> Option<TokenStream> {
+        match self.candid_type {
             CandidType::Primitive(_) => {
-                if self
-                    .to_type_annotation(context, parent_name.clone())
-                    .to_string()
-                    == quote!((())).to_string()
-                {
-                    quote!()
+                if !self.has_variant_value(context, &parent_name) {
+                    None
                 } else {
-                    let member_type_token_stream = self.to_type_annotation(context, parent_name);
-                    quote!((#member_type_token_stream))
+                    Some(self.to_type_annotation(context, parent_name))
                 }
             }
             _ => {
                 let member_type_annotation = self.to_type_annotation(context, parent_name);
-                quote!((Box<#member_type_annotation>))
+                Some(quote!(Box<#member_type_annotation>))
             }
-        };
-        let member_name = keyword::make_rust_safe(&self.name, &context.keyword_list).to_ident();
-        let rename_attr = keyword::generate_rename_attribute(&member_name, &context.keyword_list);
-        quote! {#rename_attr #member_name #member_type_token_stream}
+        }
+    }
+
+    /// Whether the case of the generated enum holds a value
+    pub fn has_variant_value(&self, context: &Context, parent_name: &str) -> bool {
+        match self.candid_type {
+            CandidType::Primitive(_) => {
+                self.to_type_annotation(context, parent_name.to_string())
+                    .to_string()
+                    != quote!((())).to_string()
+            }
+            _ => true,
+        }
     }
 }
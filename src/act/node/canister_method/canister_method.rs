@@ -4,7 +4,7 @@ use super::{
 };
 use crate::{
     act::{
-        node::{candid::TypeRef, AsNode, Context, Node, Param, ReturnType},
+        node::{candid::TypeRef, AsNode, Context, HttpHandler, Node, Param, ReturnType},
         Declaration, Declare,
     },
     traits::HasTypeRefs,
@@ -19,6 +19,7 @@ pub enum CanisterMethod {
     PostUpgrade(PostUpgradeMethod),
     InspectMessage(InspectMessageMethod),
     Heartbeat(HeartbeatMethod),
+    Http(HttpHandler),
 }
 
 impl AsNode for CanisterMethod {
@@ -38,6 +39,7 @@ impl Declare<Context> for CanisterMethod {
             CanisterMethod::PostUpgrade(post_upgrade) => to_declaration(post_upgrade),
             CanisterMethod::InspectMessage(inspect_message) => to_declaration(inspect_message),
             CanisterMethod::Heartbeat(heartbeat) => to_declaration(heartbeat),
+            CanisterMethod::Http(http_handler) => to_declaration(http_handler),
         }
     }
 
@@ -58,6 +60,7 @@ impl Declare<Context> for CanisterMethod {
                 collect_inline_declarations(inspect_message)
             }
             CanisterMethod::Heartbeat(heartbeat) => collect_inline_declarations(heartbeat),
+            CanisterMethod::Http(http_handler) => collect_inline_declarations(http_handler),
         }
     }
 }
@@ -9,11 +9,14 @@ pub struct HeartbeatMethod {
 }
 
 impl Declare<Context> for HeartbeatMethod {
-    fn to_declaration(&self, _: &Context, _: String) -> Option<Declaration> {
+    fn to_declaration(&self, context: &Context, _: String) -> Option<Declaration> {
         let body = &self.body;
+        let canister_method_attribute = context
+            .target_version
+            .generate_canister_method_attribute("heartbeat", quote!());
 
         Some(quote! {
-            #[ic_cdk_macros::heartbeat]
+            #canister_method_attribute
             fn heartbeat() {
                 #body
             }
@@ -3,15 +3,16 @@ use quote::quote;
 
 use crate::{
     act::{
+        certification,
         node::{candid::TypeRef, Context, Param, ReturnType},
-        Declaration, Declare,
+        random, Declaration, Declare,
     },
     traits::{HasInlines, HasTypeRefs, IsCallable, ToIdent},
 };
 
 use super::canister_method;
 
-#[derive(Clone)]
+#[derive(Clone, Default)]
 pub struct InitMethod {
     pub params: Vec<Param>,
     pub body: TokenStream,
@@ -27,12 +28,19 @@ impl Declare<Context> for InitMethod {
     fn to_declaration(&self, context: &Context, _: String) -> Option<Declaration> {
         let function_name = self.get_name().to_ident();
         let body = &self.body;
+        let canister_method_attribute = context
+            .target_version
+            .generate_canister_method_attribute("init", quote!());
+        let rng_install_hook = random::generate_install_hook();
+        let certification_install_hook =
+            certification::generate_install_hook(&context.certification);
         let params = self.create_parameter_list_token_stream(&self.get_name(), context);
 
         Some(quote! {
-            #[ic_cdk_macros::init]
-            #[candid::candid_method(init)]
+            #canister_method_attribute
             fn #function_name(#params) {
+                #rng_install_hook
+                #certification_install_hook
                 #body
             }
         })
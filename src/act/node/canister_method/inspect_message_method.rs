@@ -9,11 +9,14 @@ pub struct InspectMessageMethod {
 }
 
 impl Declare<Context> for InspectMessageMethod {
-    fn to_declaration(&self, _: &Context, _: String) -> Option<Declaration> {
+    fn to_declaration(&self, context: &Context, _: String) -> Option<Declaration> {
         let body = &self.body;
+        let canister_method_attribute = context
+            .target_version
+            .generate_canister_method_attribute("inspect_message", quote!());
 
         Some(quote! {
-            #[ic_cdk_macros::inspect_message]
+            #canister_method_attribute
             fn inspect_message() {
                 #body
             }
@@ -0,0 +1,90 @@
+use proc_macro2::TokenStream;
+use quote::quote;
+
+use crate::{
+    act::target_version::TargetVersion,
+    traits::{ToIdent, WithUserDefinedPrefix},
+};
+
+/// Declares which ingress messages to a query or update method are accepted
+/// by the generated inspect_message. A message is accepted only if it passes
+/// every check that is set.
+#[derive(Clone, Debug, Default)]
+pub struct InspectMessagePolicy {
+    pub reject_anonymous: bool,
+    /// The maximum size in bytes of the candid encoded arguments
+    pub max_arg_size: Option<usize>,
+    /// The textual representations of the principals that may call the method
+    pub allowed_callers: Option<Vec<String>>,
+    /// The name of a guard function that must return `Ok`
+    pub guard_function_name: Option<String>,
+}
+
+impl InspectMessagePolicy {
+    fn generate_accepts_expression(&self, target_version: TargetVersion) -> TokenStream {
+        let mut checks = vec![];
+        let caller = target_version.generate_caller();
+
+        if self.reject_anonymous {
+            checks.push(quote!(#caller != candid::Principal::anonymous()));
+        }
+        if let Some(max_arg_size) = self.max_arg_size {
+            let arg_data_size = target_version.generate_arg_data_size();
+            checks.push(quote!(#arg_data_size <= #max_arg_size));
+        }
+        if let Some(allowed_callers) = &self.allowed_callers {
+            checks.push(quote! {
+                [#(#allowed_callers),*].contains(&#caller.to_text().as_str())
+            });
+        }
+        if let Some(guard_function_name) = &self.guard_function_name {
+            let guard_function_ident = guard_function_name.with_user_defined_prefix().to_ident();
+            checks.push(quote!(#guard_function_ident().is_ok()));
+        }
+
+        if checks.is_empty() {
+            quote!(true)
+        } else {
+            quote!(#(#checks)&&*)
+        }
+    }
+}
+
+/// Generates the body of an inspect_message that applies the policy of each
+/// method. Messages to methods that aren't declared are rejected. Messages to
+/// methods without a policy are left to the fallback body, or accepted if
+/// there is none.
+pub fn generate_dispatcher_body(
+    methods: &[(String, Option<InspectMessagePolicy>)],
+    fallback_body: Option<&TokenStream>,
+    target_version: TargetVersion,
+) -> TokenStream {
+    let accept_message = target_version.generate_accept_message();
+    let method_name = target_version.generate_method_name();
+    let arms = methods.iter().map(|(name, policy)| match policy {
+        Some(policy) => {
+            let accepts = policy.generate_accepts_expression(target_version);
+            quote!(#name => Some(#accepts))
+        }
+        None => quote!(#name => None),
+    });
+    let fallback_body = match fallback_body {
+        Some(fallback_body) => quote!(#fallback_body),
+        None => quote!(#accept_message),
+    };
+
+    quote! {
+        let accepted: Option<bool> = match #method_name.as_str() {
+            #(#arms,)*
+            _ => Some(false),
+        };
+
+        match accepted {
+            Some(true) => #accept_message,
+            Some(false) => (),
+            None => {
+                #fallback_body
+            }
+        }
+    }
+}
@@ -3,6 +3,7 @@ pub mod canister_method_type;
 pub mod heartbeat_method;
 pub mod init_method;
 pub mod inspect_message_method;
+pub mod inspect_message_policy;
 pub mod post_upgrade_method;
 pub mod pre_upgrade_method;
 pub mod query_method;
@@ -16,6 +17,7 @@ pub use canister_method_type::CanisterMethodType;
 pub use heartbeat_method::HeartbeatMethod;
 pub use init_method::InitMethod;
 pub use inspect_message_method::InspectMessageMethod;
+pub use inspect_message_policy::InspectMessagePolicy;
 pub use post_upgrade_method::PostUpgradeMethod;
 pub use pre_upgrade_method::PreUpgradeMethod;
 pub use query_method::QueryMethod;
@@ -3,21 +3,31 @@ use quote::quote;
 
 use crate::{
     act::{
+        certification,
         node::{candid::TypeRef, Context, Param, ReturnType},
-        Declaration, Declare,
+        random, Declaration, Declare,
     },
     traits::{HasInlines, HasTypeRefs, IsCallable, ToIdent},
 };
 
-use super::canister_method;
+use super::{canister_method, init_method::InitMethod};
 
-#[derive(Clone)]
+#[derive(Clone, Default)]
 pub struct PostUpgradeMethod {
     pub params: Vec<Param>,
     pub body: TokenStream,
 }
 
 impl PostUpgradeMethod {
+    /// Creates a post_upgrade method with the same params as the init
+    /// method, since both receive the same install arguments
+    pub fn with_init_params(init_method: &InitMethod, body: TokenStream) -> PostUpgradeMethod {
+        PostUpgradeMethod {
+            params: init_method.params.clone(),
+            body,
+        }
+    }
+
     fn get_name(&self) -> String {
         "post_upgrade".to_string()
     }
@@ -27,11 +37,19 @@ impl Declare<Context> for PostUpgradeMethod {
     fn to_declaration(&self, context: &Context, _: String) -> Option<Declaration> {
         let function_name = self.get_name().to_ident();
         let body = &self.body;
+        let canister_method_attribute = context
+            .target_version
+            .generate_canister_method_attribute("post_upgrade", quote!());
+        let rng_install_hook = random::generate_install_hook();
+        let certification_install_hook =
+            certification::generate_install_hook(&context.certification);
         let params = self.create_parameter_list_token_stream(&self.get_name(), context);
 
         Some(quote! {
-            #[ic_cdk_macros::post_upgrade]
+            #canister_method_attribute
             fn #function_name(#params) {
+                #rng_install_hook
+                #certification_install_hook
                 #body
             }
         })
@@ -9,11 +9,14 @@ pub struct PreUpgradeMethod {
 }
 
 impl Declare<Context> for PreUpgradeMethod {
-    fn to_declaration(&self, _: &Context, _: String) -> Option<Declaration> {
+    fn to_declaration(&self, context: &Context, _: String) -> Option<Declaration> {
         let body = &self.body;
+        let canister_method_attribute = context
+            .target_version
+            .generate_canister_method_attribute("pre_upgrade", quote!());
 
         Some(quote! {
-            #[ic_cdk_macros::pre_upgrade]
+            #canister_method_attribute
             fn pre_upgrade() {
                 #body
             }
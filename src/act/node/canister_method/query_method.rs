@@ -65,7 +65,6 @@ impl Deref for QueryMethod {
 
 impl Declare<Context> for QueryMethod {
     fn to_declaration(&self, context: &Context, _: String) -> Option<Declaration> {
-        let user_defined_name = &self.name;
         let function_declaration = self.generate_function_body(context);
         let macro_args = if context.cdk_name == "kybra" {
             self.generate_kybra_macro_args()
@@ -73,9 +72,12 @@ impl Declare<Context> for QueryMethod {
             self.generate_not_kybra_macro_args()
         };
 
+        let canister_method_attribute = context
+            .target_version
+            .generate_canister_method_attribute("query", macro_args);
+
         Some(quote! {
-            #[ic_cdk_macros::query(#macro_args)]
-            #[candid::candid_method(query, rename = #user_defined_name)]
+            #canister_method_attribute
             #function_declaration
         })
     }
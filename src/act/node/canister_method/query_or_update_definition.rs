@@ -2,11 +2,14 @@ use proc_macro2::TokenStream;
 use quote::quote;
 
 use crate::{
-    act::node::{candid::TypeRef, CandidType, Context, Param, ReturnType},
+    act::{
+        docs,
+        node::{candid::TypeRef, CandidType, Context, Param, ReturnType},
+    },
     traits::{HasTypeRefs, IsCallable, ToIdent, ToTypeAnnotation, WithUserDefinedPrefix},
 };
 
-use super::canister_method;
+use super::{canister_method, InspectMessagePolicy};
 
 #[derive(Clone, Debug)]
 pub struct QueryOrUpdateDefinition {
@@ -17,6 +20,10 @@ pub struct QueryOrUpdateDefinition {
     pub params: Vec<Param>,
     pub return_type: ReturnType,
     pub body: TokenStream,
+    pub docs: Option<String>,
+    /// When set on any method, an inspect_message is generated that applies
+    /// the policies. See [`InspectMessagePolicy`].
+    pub inspect_message_policy: Option<InspectMessagePolicy>,
 }
 
 impl QueryOrUpdateDefinition {
@@ -37,6 +44,8 @@ impl QueryOrUpdateDefinition {
             params,
             return_type: ReturnType::new(return_type),
             body,
+            docs: None,
+            inspect_message_policy: None,
         }
     }
 
@@ -52,14 +61,17 @@ impl QueryOrUpdateDefinition {
 
         let wrapped_return_type =
             if self.is_manual || (self.is_async && context.cdk_name != "kybra") {
-                quote! {
-                    ic_cdk::api::call::ManualReply<(#return_type_token)>
-                }
+                context
+                    .target_version
+                    .generate_manual_reply_type(&return_type_token)
             } else {
                 return_type_token
             };
 
+        let doc_attributes = docs::generate_callable_doc_attributes(&self.docs, &self.params);
+
         quote! {
+            #doc_attributes
             async fn #function_name(#params) -> (#wrapped_return_type) {
                 #function_body
             }
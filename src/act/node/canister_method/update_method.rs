@@ -4,6 +4,7 @@ use std::ops::Deref;
 
 use crate::{
     act::{
+        certification,
         node::{canister_method::QueryOrUpdateDefinition, Context, Param, ReturnType},
         Declaration, Declare,
     },
@@ -44,13 +45,24 @@ impl Deref for UpdateMethod {
 
 impl Declare<Context> for UpdateMethod {
     fn to_declaration(&self, context: &Context, _: String) -> Option<Declaration> {
-        let user_defined_name = &self.name;
-        let function_declaration = self.generate_function_body(context);
+        let function_declaration = match &context.certification {
+            Some(certification) if certification.is_certified_update_method(&self.name) => {
+                QueryOrUpdateDefinition {
+                    body: certification::wrap_certified_update_body(&self.body),
+                    ..self.definition.clone()
+                }
+                .generate_function_body(context)
+            }
+            _ => self.generate_function_body(context),
+        };
         let macro_args = self.generate_macro_args(&context.cdk_name);
 
+        let canister_method_attribute = context
+            .target_version
+            .generate_canister_method_attribute("update", macro_args);
+
         Some(quote! {
-            #[ic_cdk_macros::update(#macro_args)]
-            #[candid::candid_method(update, rename = #user_defined_name)]
+            #canister_method_attribute
             #function_declaration
         })
     }
@@ -1,5 +1,14 @@
+use proc_macro2::TokenStream;
+
+use crate::act::{certification::Certification, target_version::TargetVersion};
+
 #[derive(Clone)]
 pub struct Context {
     pub keyword_list: Vec<String>,
     pub cdk_name: String,
+    pub default_derives: Vec<TokenStream>,
+    pub default_attributes: Vec<TokenStream>,
+    pub certification: Option<Certification>,
+    pub vm_adapter: bool,
+    pub target_version: TargetVersion,
 }
@@ -0,0 +1,239 @@
+use proc_macro2::TokenStream;
+use quote::quote;
+
+use super::{http_types, HttpRoute};
+use crate::act::{
+    certification,
+    node::{AsNode, CanisterMethod, Context, Node},
+    Declaration, Declare,
+};
+
+/// Generates the `http_request` query and, if any route upgrades, the
+/// `http_request_update` update of the HTTP gateway interface. Routes are
+/// tried in order and the first match handles the request.
+#[derive(Clone, Debug)]
+pub struct HttpHandler {
+    pub routes: Vec<HttpRoute>,
+    /// Runs when no route matches. Has `request: HttpRequest` in scope and
+    /// must evaluate to an `HttpResponse`. Defaults to a 404 response.
+    pub not_found_body: Option<TokenStream>,
+}
+
+impl HttpHandler {
+    pub fn get_method_names(&self) -> Vec<String> {
+        if self.has_upgrades() {
+            vec![
+                "http_request".to_string(),
+                "http_request_update".to_string(),
+            ]
+        } else {
+            vec!["http_request".to_string()]
+        }
+    }
+
+    fn has_upgrades(&self) -> bool {
+        self.routes.iter().any(|route| route.upgrade)
+    }
+
+    fn generate_not_found(&self) -> TokenStream {
+        match &self.not_found_body {
+            Some(not_found_body) => quote!(#not_found_body),
+            None => quote! {
+                HttpResponse {
+                    status_code: 404,
+                    headers: vec![],
+                    body: b"Not found".to_vec(),
+                    upgrade: None,
+                    streaming_strategy: None,
+                }
+            },
+        }
+    }
+
+    fn generate_http_request(&self, context: &Context) -> TokenStream {
+        let certify_http_responses = match &context.certification {
+            Some(certification) => certification.certify_http_responses,
+            None => false,
+        };
+        let canister_method_attribute = context
+            .target_version
+            .generate_canister_method_attribute("query", quote!(name = "http_request"));
+
+        if !certify_http_responses {
+            return self.generate_routing_function(quote! {
+                #canister_method_attribute
+                fn _cdk_http_request
+            });
+        }
+
+        let routing_function =
+            self.generate_routing_function(quote!(fn _cdk_http_request_uncertified));
+
+        quote! {
+            #canister_method_attribute
+            fn _cdk_http_request(request: HttpRequest) -> HttpResponse {
+                let path = request.url.split('?').next().unwrap_or("").to_string();
+                let mut response = _cdk_http_request_uncertified(request);
+
+                if let Some(header) = _cdk_http_certificate_header(&path) {
+                    response.headers.push(header);
+                }
+
+                response
+            }
+
+            #routing_function
+        }
+    }
+
+    /// Generates a function that answers requests from the query call.
+    /// `signature` is everything up to the function's name.
+    fn generate_routing_function(&self, signature: TokenStream) -> TokenStream {
+        let routes = self.routes.iter().map(|route| {
+            let condition = route.generate_match_condition();
+            let body = &route.body;
+
+            if route.upgrade {
+                quote! {
+                    if #condition.is_some() {
+                        return HttpResponse {
+                            status_code: 200,
+                            headers: vec![],
+                            body: vec![],
+                            upgrade: Some(true),
+                            streaming_strategy: None,
+                        };
+                    }
+                }
+            } else {
+                quote! {
+                    if let Some(params) = #condition {
+                        return { #body };
+                    }
+                }
+            }
+        });
+        let not_found = self.generate_not_found();
+
+        quote! {
+            #[allow(unused_variables)]
+            #signature(request: HttpRequest) -> HttpResponse {
+                #(#routes)*
+
+                #not_found
+            }
+        }
+    }
+
+    fn generate_http_request_update(&self, context: &Context) -> TokenStream {
+        let routes = self
+            .routes
+            .iter()
+            .filter(|route| route.upgrade)
+            .map(|route| {
+                let condition = route.generate_match_condition();
+                let body = &route.body;
+
+                quote! {
+                    if let Some(params) = #condition {
+                        return { #body };
+                    }
+                }
+            });
+        let not_found = self.generate_not_found();
+        let canister_method_attribute = context
+            .target_version
+            .generate_canister_method_attribute("update", quote!(name = "http_request_update"));
+
+        quote! {
+            #canister_method_attribute
+            #[allow(unused_variables)]
+            fn _cdk_http_request_update(request: HttpRequest) -> HttpResponse {
+                #(#routes)*
+
+                #not_found
+            }
+        }
+    }
+}
+
+impl AsNode for HttpHandler {
+    fn as_node(self) -> Node {
+        Node::CanisterMethod(CanisterMethod::Http(self))
+    }
+}
+
+impl Declare<Context> for HttpHandler {
+    fn to_declaration(&self, context: &Context, _: String) -> Option<Declaration> {
+        let http_request = self.generate_http_request(context);
+        let http_request_update = if self.has_upgrades() {
+            self.generate_http_request_update(context)
+        } else {
+            quote!()
+        };
+
+        Some(quote! {
+            #http_request
+            #http_request_update
+        })
+    }
+
+    fn collect_inline_declarations(&self, context: &Context, _: String) -> Vec<Declaration> {
+        let certificate_header_function = match &context.certification {
+            Some(certification) if certification.certify_http_responses => {
+                vec![certification::generate_http_certificate_header_function()]
+            }
+            _ => vec![],
+        };
+
+        [
+            vec![
+                http_types::generate_http_types(context),
+                generate_match_route_function(),
+            ],
+            certificate_header_function,
+        ]
+        .concat()
+    }
+}
+
+fn generate_match_route_function() -> TokenStream {
+    quote! {
+        fn _cdk_http_match_route(
+            method: Option<&str>,
+            pattern: &str,
+            request: &HttpRequest,
+        ) -> Option<std::collections::HashMap<String, String>> {
+            if let Some(method) = method {
+                if !request.method.eq_ignore_ascii_case(method) {
+                    return None;
+                }
+            }
+
+            let path = request.url.split('?').next().unwrap_or("");
+            let mut path_segments = path.split('/').filter(|segment| !segment.is_empty());
+            let mut params = std::collections::HashMap::new();
+
+            for pattern_segment in pattern.split('/').filter(|segment| !segment.is_empty()) {
+                if pattern_segment == "*" {
+                    return Some(params);
+                }
+
+                let path_segment = path_segments.next()?;
+
+                match pattern_segment.strip_prefix(':') {
+                    Some(name) => {
+                        params.insert(name.to_string(), path_segment.to_string());
+                    }
+                    None if pattern_segment == path_segment => (),
+                    None => return None,
+                }
+            }
+
+            match path_segments.next() {
+                Some(_) => None,
+                None => Some(params),
+            }
+        }
+    }
+}
@@ -0,0 +1,33 @@
+use proc_macro2::TokenStream;
+use quote::quote;
+
+/// A handler for the HTTP requests whose method and path match. The body
+/// has `request: HttpRequest` and
+/// `params: std::collections::HashMap<String, String>` in scope and must
+/// evaluate to an `HttpResponse`.
+#[derive(Clone, Debug)]
+pub struct HttpRoute {
+    /// The HTTP method to match, e.g. "GET". `None` matches every method.
+    pub method: Option<String>,
+    /// The path to match. A segment like `:id` matches any single segment
+    /// and is added to `params`, and a final `*` matches the rest of the
+    /// path. The query string is ignored.
+    pub path: String,
+    pub body: TokenStream,
+    /// Whether the route changes state. Queries answer these requests with
+    /// `upgrade = opt true` so that the boundary node repeats the request as
+    /// an `http_request_update` call, where the body runs.
+    pub upgrade: bool,
+}
+
+impl HttpRoute {
+    pub fn generate_match_condition(&self) -> TokenStream {
+        let method = match &self.method {
+            Some(method) => quote!(Some(#method)),
+            None => quote!(None),
+        };
+        let path = &self.path;
+
+        quote!(_cdk_http_match_route(#method, #path, &request))
+    }
+}
@@ -0,0 +1,189 @@
+use proc_macro2::TokenStream;
+use quote::quote;
+
+use crate::{
+    act::{
+        interfaces::{
+            builders::{
+                func, inline_record, member, opt, primitive, record, type_ref, variant, vec,
+            },
+            VmConversionHooks,
+        },
+        node::{
+            candid::{tuple::Elem, type_param::TypeParams, Primitive, Tuple},
+            node_parts::mode::Mode,
+            CandidType, Context, Member,
+        },
+        CandidTypes, ToTypeAnnotation,
+    },
+    traits::{IsCallable, ToIdent},
+};
+
+/// The Candid types of the HTTP gateway interface, which the boundary nodes
+/// use to call `http_request` and `http_request_update`. They are declared
+/// by [generate_http_types] and written to the .did file by
+/// [did](crate::act::did).
+pub fn http_types() -> CandidTypes {
+    CandidTypes {
+        funcs: vec![func(
+            Some("StreamingCallback"),
+            vec![type_ref("StreamingCallbackToken")],
+            type_ref("StreamingCallbackHttpResponse"),
+            Mode::Query,
+            VmConversionHooks::none(),
+        )],
+        records: vec![
+            record(
+                Some("HttpRequest"),
+                vec![
+                    member("method", text()),
+                    member("url", text()),
+                    member("headers", vec(type_ref("HeaderField"))),
+                    member("body", primitive(Primitive::Blob)),
+                    member("certificate_version", opt(primitive(Primitive::Nat16))),
+                ],
+            ),
+            record(
+                Some("HttpResponse"),
+                vec![
+                    member("status_code", primitive(Primitive::Nat16)),
+                    member("headers", vec(type_ref("HeaderField"))),
+                    member("body", primitive(Primitive::Blob)),
+                    member("upgrade", opt(primitive(Primitive::Bool))),
+                    member("streaming_strategy", opt(type_ref("StreamingStrategy"))),
+                ],
+            ),
+            record(
+                Some("StreamingCallbackHttpResponse"),
+                vec![
+                    member("body", primitive(Primitive::Blob)),
+                    member("token", opt(type_ref("StreamingCallbackToken"))),
+                ],
+            ),
+            record(
+                Some("StreamingCallbackToken"),
+                vec![
+                    member("key", text()),
+                    member("content_encoding", text()),
+                    member("index", primitive(Primitive::Nat)),
+                    member("sha256", opt(primitive(Primitive::Blob))),
+                ],
+            ),
+        ],
+        services: vec![],
+        tuples: vec![Tuple {
+            name: Some("HeaderField".to_string()),
+            elems: vec![
+                Elem {
+                    candid_type: text(),
+                },
+                Elem {
+                    candid_type: text(),
+                },
+            ],
+            type_params: TypeParams(vec![]),
+            derives: vec![],
+            attributes: vec![],
+            docs: None,
+        }],
+        type_aliases: vec![],
+        variants: vec![variant(
+            Some("StreamingStrategy"),
+            vec![member(
+                "Callback",
+                inline_record(vec![
+                    member("callback", type_ref("StreamingCallback")),
+                    member("token", type_ref("StreamingCallbackToken")),
+                ]),
+            )],
+        )],
+    }
+}
+
+/// Declares [http_types]. Handlers and route bodies build these types
+/// directly, so their fields aren't boxed like those of the canister's
+/// records, and they only get the derives they are encoded and decoded with.
+pub fn generate_http_types(context: &Context) -> TokenStream {
+    let http_types = http_types();
+    let derives = quote!(#[derive(candid::CandidType, serde::Deserialize, Clone, Debug)]);
+    let to_type_annotation =
+        |candid_type: &CandidType| candid_type.to_type_annotation(context, String::new());
+    let to_fields = |members: &[Member]| -> Vec<TokenStream> {
+        members
+            .iter()
+            .map(|member| {
+                let name = member.name.to_ident();
+                let type_annotation = to_type_annotation(&member.candid_type);
+
+                quote!(#name: #type_annotation)
+            })
+            .collect()
+    };
+
+    let records = http_types.records.iter().map(|record| {
+        let name = record.name.clone().unwrap_or_default().to_ident();
+        let fields = to_fields(&record.members);
+
+        quote! {
+            #derives
+            struct #name {
+                #(#fields),*
+            }
+        }
+    });
+    let tuples = http_types.tuples.iter().map(|tuple| {
+        let name = tuple.name.clone().unwrap_or_default().to_ident();
+        let elems = tuple
+            .elems
+            .iter()
+            .map(|elem| to_type_annotation(&elem.candid_type));
+
+        quote! {
+            #derives
+            struct #name(#(#elems),*);
+        }
+    });
+    let variants = http_types.variants.iter().map(|variant| {
+        let name = variant.name.clone().unwrap_or_default().to_ident();
+        let cases = variant.members.iter().map(|member| {
+            let case_name = member.name.to_ident();
+
+            match &member.candid_type {
+                CandidType::Record(record) => {
+                    let fields = to_fields(&record.members);
+                    quote!(#case_name { #(#fields),* })
+                }
+                CandidType::Primitive(Primitive::Null) => quote!(#case_name),
+                candid_type => {
+                    let type_annotation = to_type_annotation(candid_type);
+                    quote!(#case_name(#type_annotation))
+                }
+            }
+        });
+
+        quote! {
+            #derives
+            enum #name {
+                #(#cases),*
+            }
+        }
+    });
+    let funcs = http_types.funcs.iter().map(|func| {
+        let name = func.name.clone().unwrap_or_default();
+        let func_macro_token_stream = func.get_func_macro_token_stream(&name, context, &func.mode);
+        let name = name.to_ident();
+
+        quote!(candid::define_function!(pub #name : #func_macro_token_stream);)
+    });
+
+    quote! {
+        #(#records)*
+        #(#tuples)*
+        #(#variants)*
+        #(#funcs)*
+    }
+}
+
+fn text() -> CandidType {
+    primitive(Primitive::String)
+}
@@ -0,0 +1,6 @@
+pub mod http_handler;
+pub mod http_route;
+pub mod http_types;
+
+pub use http_handler::HttpHandler;
+pub use http_route::HttpRoute;
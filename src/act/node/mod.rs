@@ -2,15 +2,19 @@ pub mod candid;
 pub mod canister_method;
 pub mod context;
 pub mod guard_function;
+pub mod http;
 pub mod node;
 pub mod node_parts;
+pub mod transform_function;
 
 pub use candid::CandidType;
 pub use canister_method::CanisterMethod;
 pub use context::Context;
 pub use guard_function::GuardFunction;
+pub use http::HttpHandler;
 pub use node::AsNode;
 pub use node::Node;
 pub use node_parts::member::Member;
 pub use node_parts::param::Param;
 pub use node_parts::return_type::ReturnType;
+pub use transform_function::TransformFunction;
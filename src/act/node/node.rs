@@ -1,5 +1,5 @@
 use crate::act::{
-    node::{CandidType, CanisterMethod, Context, GuardFunction},
+    node::{CandidType, CanisterMethod, Context, GuardFunction, TransformFunction},
     Declaration, Declare,
 };
 
@@ -8,6 +8,7 @@ pub enum Node {
     CanisterMethod(CanisterMethod),
     CandidType(CandidType),
     GuardFunction(GuardFunction),
+    TransformFunction(TransformFunction),
 }
 
 pub trait AsNode {
@@ -24,6 +25,9 @@ impl Declare<Context> for Node {
             Node::GuardFunction(guard_function) => {
                 guard_function.to_declaration(context, inline_name)
             }
+            Node::TransformFunction(transform_function) => {
+                transform_function.to_declaration(context, inline_name)
+            }
         }
     }
 
@@ -42,6 +46,9 @@ impl Declare<Context> for Node {
             Node::GuardFunction(guard_function) => {
                 guard_function.collect_inline_declarations(context, inline_name)
             }
+            Node::TransformFunction(transform_function) => {
+                transform_function.collect_inline_declarations(context, inline_name)
+            }
         }
     }
 }
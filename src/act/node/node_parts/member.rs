@@ -1,15 +1,54 @@
+use proc_macro2::{Ident, TokenStream};
+use quote::quote;
+
 use crate::{
     act::{
         node::{CandidType, Context},
         Declaration, Declare, TypeAnnotation,
     },
-    traits::{HasInlineName, ToTypeAnnotation},
+    keyword, label,
+    traits::{HasInlineName, ToIdent, ToTypeAnnotation},
 };
 
 #[derive(Clone, Debug)]
 pub struct Member {
     pub name: String,
     pub candid_type: CandidType,
+    pub docs: Option<String>,
+    /// An explicit numeric label for candid labels like `0` in
+    /// `record { 0: nat; 42: text }`, or the hash of a label whose text
+    /// isn't known. The name is still used for inline type names and docs.
+    pub label_id: Option<u32>,
+}
+
+impl Member {
+    /// The identifier of the member in the generated record or variant and
+    /// the serde rename attribute, if any, needed to restore its candid label
+    pub fn to_rust_name_and_rename_attribute(&self, context: &Context) -> (Ident, TokenStream) {
+        match self.label_id {
+            Some(id) => (label::from_label_id(id).to_ident(), quote!()),
+            None => {
+                let name = keyword::make_rust_safe(&self.name, &context.keyword_list).to_ident();
+                let rename_attr = keyword::generate_rename_attribute(&name, &context.keyword_list);
+                (name, rename_attr)
+            }
+        }
+    }
+}
+
+impl Member {
+    /// The identifier of the member in the generated record or variant and
+    /// the name the VM knows it by. Label ids are given to the VM as their
+    /// number.
+    pub fn to_rust_and_vm_names(&self, context: &Context) -> (Ident, String) {
+        let (rust_name, _) = self.to_rust_name_and_rename_attribute(context);
+        let vm_name = match self.label_id {
+            Some(id) => id.to_string(),
+            None => keyword::restore_for_vm(&rust_name.to_string(), &context.keyword_list),
+        };
+
+        (rust_name, vm_name)
+    }
 }
 
 impl ToTypeAnnotation<Context> for Member {
@@ -36,6 +75,9 @@ impl Declare<Context> for Member {
 
 impl HasInlineName for Member {
     fn get_inline_name(&self, parent_name: &String) -> String {
-        format!("{parent_name}_{member_name}", member_name = self.name)
+        format!(
+            "{parent_name}_{member_name}",
+            member_name = label::to_rust_identifier(&self.name)
+        )
     }
 }
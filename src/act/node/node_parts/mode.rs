@@ -1,6 +1,9 @@
 #[derive(Clone, Debug)]
 pub enum Mode {
     Query,
+    /// A query that may call other queries. Only funcs and service methods
+    /// can be composite queries, a server generates them as plain queries.
+    CompositeQuery,
     Update,
     Oneway,
 }
@@ -12,6 +12,7 @@ use crate::{
 pub struct Param {
     pub name: String,
     pub candid_type: CandidType,
+    pub docs: Option<String>,
 }
 
 impl Param {
@@ -0,0 +1,222 @@
+use proc_macro2::TokenStream;
+use quote::quote;
+
+use crate::{
+    act::{
+        compatibility::{self, Interface},
+        interfaces::{self, VmConversionHooks},
+        node::{candid::TypeRef, node_parts::mode::Mode, AsNode, Node, Param, ReturnType},
+        target_version::{Payment, TargetVersion},
+        CandidTypes, Declaration, Declare,
+    },
+    traits::{
+        HasDefinedNames, HasInlines, HasTypeRefs, IsCallable, ToIdent, ToTypeAnnotation,
+        WithUserDefinedPrefix,
+    },
+};
+
+use super::{canister_method, Context};
+
+/// A query that transforms the responses of HTTPS outcalls. Its signature
+/// must be `(TransformArgs) -> HttpResponse` as defined by the management
+/// canister. It is left out of the candid file since it is only meant to be
+/// called by the system.
+#[derive(Debug, Clone)]
+pub struct TransformFunction {
+    pub name: String,
+    pub params: Vec<Param>,
+    pub return_type: ReturnType,
+    pub body: TokenStream,
+}
+
+impl TransformFunction {
+    /// Checks that the system can call the function with the management
+    /// canister's `TransformArgs` and decode its return value as an
+    /// `HttpResponse`, by comparing them with the `http_transform_function`
+    /// of [management_canister](crate::act::interfaces::management_canister)
+    pub fn verify_signature(&self, candid_types: &CandidTypes) -> Result<(), String> {
+        let management_canister = interfaces::management_canister(VmConversionHooks::none());
+        let expected = management_canister
+            .funcs
+            .iter()
+            .find(|func| func.name.as_deref() == Some("http_transform_function"))
+            .expect("the management canister declares http_transform_function");
+
+        let expected_interface = Interface::with_method(
+            &management_canister,
+            &self.name,
+            expected.mode.clone(),
+            expected.params.clone(),
+            &expected.return_type,
+        );
+        let interface = Interface::with_method(
+            candid_types,
+            &self.name,
+            Mode::Query,
+            self.params
+                .iter()
+                .map(|param| param.candid_type.clone())
+                .collect(),
+            &self.return_type,
+        );
+
+        compatibility::check_interface_compatibility(&expected_interface, &interface).map_err(
+            |incompatibilities| {
+                incompatibilities
+                    .iter()
+                    .map(|incompatibility| {
+                        // The first segment is the method, which the error already names
+                        format!(
+                            "{}: {}",
+                            incompatibility.path[1..].join(" -> "),
+                            incompatibility.reason
+                        )
+                    })
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            },
+        )
+    }
+}
+
+impl AsNode for TransformFunction {
+    fn as_node(self) -> Node {
+        Node::TransformFunction(self)
+    }
+}
+
+impl Declare<Context> for TransformFunction {
+    fn to_declaration(&self, context: &Context, _: String) -> Option<Declaration> {
+        let user_defined_name = &self.name;
+        let function_name = self.name.with_user_defined_prefix().to_ident();
+        let params = self.create_parameter_list_token_stream(&self.name, context);
+        let return_type = self
+            .return_type
+            .to_type_annotation(context, self.name.clone());
+        let body = &self.body;
+        // Transform functions are left out of the candid, which ic-cdk 0.18
+        // would otherwise export them into
+        let macro_args = match context.target_version {
+            TargetVersion::IcCdk0_6 => quote!(name = #user_defined_name),
+            TargetVersion::IcCdk0_18 => quote!(name = #user_defined_name, hidden = true),
+        };
+        let canister_method_attribute = context
+            .target_version
+            .generate_canister_method_attribute("query", macro_args);
+
+        Some(quote! {
+            #canister_method_attribute
+            fn #function_name(#params) -> #return_type {
+                #body
+            }
+        })
+    }
+
+    fn collect_inline_declarations(&self, context: &Context, _: String) -> Vec<Declaration> {
+        self.flatten_inlines(self.name.clone(), context)
+    }
+}
+
+impl IsCallable for TransformFunction {
+    fn get_params(&self) -> Vec<Param> {
+        self.params.clone()
+    }
+
+    fn get_return_type(&self) -> Option<ReturnType> {
+        Some(self.return_type.clone())
+    }
+}
+
+impl HasTypeRefs for TransformFunction {
+    fn get_type_refs(&self) -> Vec<TypeRef> {
+        canister_method::get_type_refs(&self.params, Some(&self.return_type))
+    }
+}
+
+impl HasDefinedNames for Vec<TransformFunction> {
+    fn get_defined_names(&self) -> Vec<String> {
+        self.iter().map(|f| f.name.clone()).collect()
+    }
+}
+
+/// Generates `_cdk_http_outcall`, which makes an HTTPS outcall through the
+/// management canister with the given cycles attached. The transform is
+/// referenced through the generated `_CdkTransformFunction` enum, which has
+/// a variant for every declared transform function, so that only declared
+/// transforms can be used.
+pub fn generate_http_outcall_binding(
+    transform_functions: &[TransformFunction],
+    target_version: TargetVersion,
+) -> TokenStream {
+    if transform_functions.is_empty() {
+        return quote!();
+    }
+
+    let variants: Vec<_> = transform_functions
+        .iter()
+        .map(|transform_function| {
+            transform_function
+                .name
+                .with_user_defined_prefix()
+                .to_ident()
+        })
+        .collect();
+    let names = transform_functions
+        .iter()
+        .map(|transform_function| &transform_function.name);
+    let (http_request, arg_type, result_type) = match target_version {
+        TargetVersion::IcCdk0_6 => (
+            quote!(ic_cdk::api::management_canister::http_request),
+            quote!(CanisterHttpRequestArgument),
+            quote!(HttpResponse),
+        ),
+        TargetVersion::IcCdk0_18 => (
+            quote!(ic_cdk::management_canister),
+            quote!(HttpRequestArgs),
+            quote!(HttpRequestResult),
+        ),
+    };
+    let call_result_type =
+        target_version.generate_call_result_type(&quote!(#http_request::#result_type));
+    let canister_id = target_version.generate_canister_id();
+    let call = target_version.generate_call(
+        quote!(candid::Principal::management_canister()),
+        quote!("http_request"),
+        quote!((arg,)),
+        &Payment::Cycles128(quote!(cycles)),
+    );
+
+    quote! {
+        #[allow(non_camel_case_types)]
+        #[derive(Clone, Copy, Debug)]
+        enum _CdkTransformFunction {
+            #(#variants),*
+        }
+
+        impl _CdkTransformFunction {
+            fn name(&self) -> &'static str {
+                match self {
+                    #(_CdkTransformFunction::#variants => #names),*
+                }
+            }
+        }
+
+        async fn _cdk_http_outcall(
+            mut arg: #http_request::#arg_type,
+            transform: Option<(_CdkTransformFunction, Vec<u8>)>,
+            cycles: u128,
+        ) -> #call_result_type {
+            arg.transform = transform.map(|(transform_function, context)| {
+                #http_request::TransformContext {
+                    function: #http_request::TransformFunc(candid::Func {
+                        principal: #canister_id,
+                        method: transform_function.name().to_string(),
+                    }),
+                    context,
+                }
+            });
+
+            #call.map(|(response,)| response)
+        }
+    }
+}
@@ -1,18 +1,144 @@
 use proc_macro2::TokenStream;
 use quote::quote;
 
-pub fn generate_randomness_implementation() -> TokenStream {
+use crate::act::target_version::TargetVersion;
+
+/// How the generated canister seeds the RNG behind `getrandom`. Seeds come
+/// from the management canister's `raw_rand`. Calls can't be made during
+/// init and post_upgrade, so the generated init and post_upgrade glue sets
+/// timers that seed the RNG right after them. Timers need the `timers`
+/// feature of ic-cdk 0.6 or the `ic-cdk-timers` crate with ic-cdk 0.18.
+///
+/// The default seeds the RNG right after install and upgrade, and serves
+/// randomness from an all zero seed until then. [`Randomness::unseeded`]
+/// opts out of the seeding.
+#[derive(Clone, Debug)]
+pub struct Randomness {
+    /// Seeds the RNG right after the canister is installed or upgraded. The
+    /// init and post_upgrade glue is generated even if the CDK doesn't
+    /// declare those methods.
+    pub seed_on_install: bool,
+    /// Reseeds the RNG every this many seconds. Like `seed_on_install` it
+    /// adds init and post_upgrade glue.
+    pub reseed_interval_seconds: Option<u64>,
+    /// Refuses to serve randomness until the RNG has been seeded. Otherwise
+    /// randomness comes from an all zero seed until then.
+    pub require_seed: bool,
+}
+
+impl Default for Randomness {
+    fn default() -> Self {
+        Randomness {
+            seed_on_install: true,
+            reseed_interval_seconds: None,
+            require_seed: false,
+        }
+    }
+}
+
+impl Randomness {
+    /// Keeps the RNG at the all zero seed until the CDK calls `rng_seed()`.
+    /// No init or post_upgrade glue or timers are added.
+    pub fn unseeded() -> Self {
+        Randomness {
+            seed_on_install: false,
+            reseed_interval_seconds: None,
+            require_seed: false,
+        }
+    }
+}
+
+/// The call the init and post_upgrade glue makes to start seeding
+pub fn generate_install_hook() -> TokenStream {
+    quote! {
+        _cdk_rng_init();
+    }
+}
+
+/// Generates the RNG used by `getrandom` on wasm32-unknown-unknown, the
+/// seeding of it and of the `ic-wasi-polyfill` RNG on WASI, and
+/// - `_cdk_random_bytes(length: usize) -> Result<Vec<u8>, String>` for the
+///   VM to get randomness from. It fails when the RNG isn't seeded yet and
+///   [`Randomness::require_seed`] is set.
+/// - `rng_seed()` to reseed the RNG on demand
+pub fn generate_randomness_implementation(
+    randomness: &Randomness,
+    target_version: TargetVersion,
+) -> TokenStream {
+    let require_seed = randomness.require_seed;
+    let spawn_reseed = target_version.generate_spawn(quote!(_cdk_rng_reseed_or_log()));
+
+    let seed_on_install = if randomness.seed_on_install {
+        let set_timer = target_version
+            .generate_set_timer(quote!(std::time::Duration::ZERO), quote!(|| #spawn_reseed));
+
+        quote!(#set_timer;)
+    } else {
+        quote!()
+    };
+
+    let reseed_interval = match randomness.reseed_interval_seconds {
+        Some(reseed_interval_seconds) => {
+            let set_timer_interval = target_version.generate_set_timer_interval(
+                quote!(std::time::Duration::from_secs(#reseed_interval_seconds)),
+                quote!(|| #spawn_reseed),
+            );
+
+            quote!(#set_timer_interval;)
+        }
+        None => quote!(),
+    };
+
+    let raw_rand = match target_version {
+        TargetVersion::IcCdk0_6 => quote! {
+            let result: ic_cdk::api::call::CallResult<(Vec<u8>,)> =
+                ic_cdk::api::management_canister::main::raw_rand().await;
+            let (randomness,) = result.map_err(|(rejection_code, message)| {
+                format!("raw_rand was rejected with {:?}: {}", rejection_code, message)
+            })?;
+        },
+        TargetVersion::IcCdk0_18 => quote! {
+            let randomness = ic_cdk::management_canister::raw_rand()
+                .await
+                .map_err(|err| format!("raw_rand failed: {}", err))?;
+        },
+    };
+
+    let spawn_rng_seed = target_version.generate_spawn(quote! {
+        async move {
+            if let Err(err) = _cdk_rng_reseed().await {
+                ic_cdk::trap(&format!("Could not seed the RNG: {}", err));
+            }
+        }
+    });
+
     quote! {
+        const _CDK_RNG_REQUIRE_SEED: bool = #require_seed;
+
+        thread_local! {
+            static _CDK_RNG_SEEDED: std::cell::Cell<bool> = std::cell::Cell::new(false);
+        }
+
+        fn _cdk_rng_is_available() -> bool {
+            !_CDK_RNG_REQUIRE_SEED || _CDK_RNG_SEEDED.with(|seeded| seeded.get())
+        }
+
         #[cfg(all(target_arch = "wasm32", target_os = "unknown"))]
         thread_local! {
             static _CDK_RNG_REF_CELL: std::cell::RefCell<rand::rngs::StdRng> = std::cell::RefCell::new(rand::SeedableRng::from_seed([0u8; 32]));
         }
 
         #[cfg(all(target_arch = "wasm32", target_os = "unknown"))]
-        fn custom_getrandom(_buf: &mut [u8]) -> Result<(), getrandom::Error> {
+        fn custom_getrandom(buf: &mut [u8]) -> Result<(), getrandom::Error> {
+            if !_cdk_rng_is_available() {
+                return Err(getrandom::Error::from(
+                    std::num::NonZeroU32::new(getrandom::Error::CUSTOM_START).unwrap(),
+                ));
+            }
+
             _CDK_RNG_REF_CELL.with(|rng_ref_cell| {
                 let mut rng = rng_ref_cell.borrow_mut();
-                rng.fill(_buf);
+                rand::RngCore::fill_bytes(&mut *rng, buf);
             });
 
             Ok(())
@@ -22,31 +148,64 @@ pub fn generate_randomness_implementation() -> TokenStream {
         getrandom::register_custom_getrandom!(custom_getrandom);
 
         #[cfg(all(target_arch = "wasm32", target_os = "unknown"))]
-        fn rng_seed() {
-            ic_cdk::spawn(async move {
-                let result: ic_cdk::api::call::CallResult<(Vec<u8>,)> = ic_cdk::api::management_canister::main::raw_rand().await;
+        fn _cdk_rng_set_seed(randomness: &[u8]) -> Result<(), String> {
+            let seed: [u8; 32] = randomness.try_into().map_err(|_| {
+                format!("Expected 32 bytes of randomness but got {}", randomness.len())
+            })?;
 
-                _CDK_RNG_REF_CELL.with(|rng_ref_cell| {
-                    let mut rng = rng_ref_cell.borrow_mut();
-
-                    match result {
-                        Ok(randomness) => *rng = rand::SeedableRng::from_seed(randomness.0[..].try_into().unwrap()),
-                        Err(err) => panic!(err)
-                    };
-                });
+            _CDK_RNG_REF_CELL.with(|rng_ref_cell| {
+                *rng_ref_cell.borrow_mut() = rand::SeedableRng::from_seed(seed);
             });
+
+            Ok(())
         }
 
         #[cfg(all(target_arch = "wasm32", target_os = "wasi"))]
+        fn _cdk_rng_set_seed(randomness: &[u8]) -> Result<(), String> {
+            ic_wasi_polyfill::init_seed(randomness);
+
+            Ok(())
+        }
+
+        #[cfg(not(target_arch = "wasm32"))]
+        fn _cdk_rng_set_seed(_randomness: &[u8]) -> Result<(), String> {
+            Ok(())
+        }
+
+        async fn _cdk_rng_reseed() -> Result<(), String> {
+            #raw_rand
+
+            _cdk_rng_set_seed(&randomness)?;
+            _CDK_RNG_SEEDED.with(|seeded| seeded.set(true));
+
+            Ok(())
+        }
+
+        /// Failed reseeds keep the previous seed
+        async fn _cdk_rng_reseed_or_log() {
+            if let Err(err) = _cdk_rng_reseed().await {
+                ic_cdk::println!("Could not seed the RNG: {}", err);
+            }
+        }
+
+        fn _cdk_rng_init() {
+            #seed_on_install
+            #reseed_interval
+        }
+
         fn rng_seed() {
-            ic_cdk::spawn(async move {
-                let result: ic_cdk::api::call::CallResult<(Vec<u8>,)> = ic_cdk::api::management_canister::main::raw_rand().await;
+            #spawn_rng_seed;
+        }
 
-                match result {
-                    Ok(randomness) => ic_wasi_polyfill::init_seed(&randomness.0),
-                    Err(err) => panic!(err)
-                };
-            });
+        fn _cdk_random_bytes(length: usize) -> Result<Vec<u8>, String> {
+            if !_cdk_rng_is_available() {
+                return Err("Randomness is not available until the RNG is seeded".to_string());
+            }
+
+            let mut bytes = vec![0u8; length];
+            getrandom::getrandom(&mut bytes).map_err(|err| err.to_string())?;
+
+            Ok(bytes)
         }
     }
 }
@@ -0,0 +1,293 @@
+use proc_macro2::TokenStream;
+use quote::{format_ident, quote};
+
+/// The ic-cdk release the generated code is written against. ic-cdk 0.18
+/// moved the canister method macros into `ic_cdk`, replaced
+/// `ic_cdk::api::call` with the `ic_cdk::call::Call` builder, and moved the
+/// timers into the `ic-cdk-timers` crate.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum TargetVersion {
+    /// ic-cdk 0.6 with ic-cdk-macros 0.6 and candid 0.8
+    #[default]
+    IcCdk0_6,
+    /// ic-cdk 0.18 with ic-cdk-timers 0.12, candid 0.10 and candid_parser
+    IcCdk0_18,
+}
+
+/// The cycles attached to a call
+pub enum Payment {
+    None,
+    /// An expression of type `u64`
+    Cycles(TokenStream),
+    /// An expression of type `u128`
+    Cycles128(TokenStream),
+}
+
+impl Payment {
+    /// The cycles as a `u128` expression
+    fn to_u128(&self) -> Option<TokenStream> {
+        match self {
+            Payment::None => None,
+            Payment::Cycles(cycles) => Some(quote!(u128::from(#cycles))),
+            Payment::Cycles128(cycles) => Some(quote!(#cycles)),
+        }
+    }
+}
+
+impl TargetVersion {
+    /// The attribute that turns a function into a canister method, where
+    /// `kind` is `init`, `query`, `update`, `heartbeat` etc.
+    pub fn generate_canister_method_attribute(&self, kind: &str, args: TokenStream) -> TokenStream {
+        let kind = format_ident!("{}", kind);
+        let args = if args.is_empty() {
+            quote!()
+        } else {
+            quote!((#args))
+        };
+
+        match self {
+            TargetVersion::IcCdk0_6 => quote!(#[ic_cdk_macros::#kind #args]),
+            TargetVersion::IcCdk0_18 => quote!(#[ic_cdk::#kind #args]),
+        }
+    }
+
+    /// The return type of a method that replies manually. ic-cdk 0.18 has no
+    /// `ManualReply`, so its reply type only shows up in the .did file of
+    /// [did](crate::act::did), which is also what `get_candid_pointer`
+    /// returns.
+    pub fn generate_manual_reply_type(&self, return_type: &TokenStream) -> TokenStream {
+        match self {
+            TargetVersion::IcCdk0_6 => quote!(ic_cdk::api::call::ManualReply<(#return_type)>),
+            TargetVersion::IcCdk0_18 => quote!(()),
+        }
+    }
+
+    pub fn generate_call_result_type(&self, reply_type: &TokenStream) -> TokenStream {
+        match self {
+            TargetVersion::IcCdk0_6 => quote!(ic_cdk::api::call::CallResult<#reply_type>),
+            TargetVersion::IcCdk0_18 => quote!(Result<#reply_type, ic_cdk::call::Error>),
+        }
+    }
+
+    pub fn generate_notify_result_type(&self) -> TokenStream {
+        match self {
+            TargetVersion::IcCdk0_6 => quote!(Result<(), ic_cdk::api::call::RejectionCode>),
+            TargetVersion::IcCdk0_18 => quote!(Result<(), ic_cdk::call::OnewayError>),
+        }
+    }
+
+    /// An expression that awaits a call with the tuple `args` and evaluates
+    /// to a result of the type from [TargetVersion::generate_call_result_type]
+    pub fn generate_call(
+        &self,
+        canister_id: TokenStream,
+        method: TokenStream,
+        args: TokenStream,
+        payment: &Payment,
+    ) -> TokenStream {
+        match self {
+            TargetVersion::IcCdk0_6 => match payment {
+                Payment::None => quote! {
+                    ic_cdk::api::call::call(#canister_id, #method, #args).await
+                },
+                Payment::Cycles(cycles) => quote! {
+                    ic_cdk::api::call::call_with_payment(#canister_id, #method, #args, #cycles).await
+                },
+                Payment::Cycles128(cycles) => quote! {
+                    ic_cdk::api::call::call_with_payment128(#canister_id, #method, #args, #cycles)
+                        .await
+                },
+            },
+            TargetVersion::IcCdk0_18 => {
+                let with_cycles = generate_with_cycles(payment);
+
+                quote! {
+                    async {
+                        let response = ic_cdk::call::Call::unbounded_wait(#canister_id, #method)
+                            .with_args(&#args)
+                            #with_cycles
+                            .await?;
+
+                        Ok::<_, ic_cdk::call::Error>(response.candid_tuple()?)
+                    }
+                    .await
+                }
+            }
+        }
+    }
+
+    /// An expression that makes a oneway call and evaluates to a result of
+    /// the type from [TargetVersion::generate_notify_result_type]
+    pub fn generate_notify(
+        &self,
+        canister_id: TokenStream,
+        method: TokenStream,
+        args: TokenStream,
+        payment: &Payment,
+    ) -> TokenStream {
+        match self {
+            TargetVersion::IcCdk0_6 => match payment.to_u128() {
+                None => quote! {
+                    ic_cdk::api::call::notify(#canister_id, #method, #args)
+                },
+                Some(cycles) => quote! {
+                    ic_cdk::api::call::notify_with_payment128(#canister_id, #method, #args, #cycles)
+                },
+            },
+            TargetVersion::IcCdk0_18 => {
+                let with_cycles = generate_with_cycles(payment);
+
+                quote! {
+                    ic_cdk::call::Call::unbounded_wait(#canister_id, #method)
+                        .with_args(&#args)
+                        #with_cycles
+                        .oneway()
+                }
+            }
+        }
+    }
+
+    /// An expression that awaits a call with the already encoded `args` and
+    /// evaluates to a result of the undecoded reply
+    pub fn generate_call_raw(
+        &self,
+        canister_id: TokenStream,
+        method: TokenStream,
+        args: TokenStream,
+        payment: &Payment,
+    ) -> TokenStream {
+        match self {
+            TargetVersion::IcCdk0_6 => match payment {
+                Payment::None => quote! {
+                    ic_cdk::api::call::call_raw(#canister_id, #method, #args, 0).await
+                },
+                Payment::Cycles(cycles) => quote! {
+                    ic_cdk::api::call::call_raw(#canister_id, #method, #args, #cycles).await
+                },
+                Payment::Cycles128(cycles) => quote! {
+                    ic_cdk::api::call::call_raw128(#canister_id, #method, #args, #cycles).await
+                },
+            },
+            TargetVersion::IcCdk0_18 => {
+                let with_cycles = generate_with_cycles(payment);
+
+                quote! {
+                    ic_cdk::call::Call::unbounded_wait(#canister_id, #method)
+                        .with_raw_args(&#args)
+                        #with_cycles
+                        .await
+                        .map(|response| response.into_bytes())
+                        .map_err(ic_cdk::call::Error::from)
+                }
+            }
+        }
+    }
+
+    /// An expression that fails a mocked call with `message`. ic-cdk 0.18's
+    /// call errors can only be created by the system, so the mock panics.
+    pub fn generate_mock_call_failure(&self, message: TokenStream) -> TokenStream {
+        match self {
+            TargetVersion::IcCdk0_6 => quote! {
+                Err((ic_cdk::api::call::RejectionCode::CanisterError, #message))
+            },
+            TargetVersion::IcCdk0_18 => quote!(panic!("{}", #message)),
+        }
+    }
+
+    pub fn generate_spawn(&self, future: TokenStream) -> TokenStream {
+        match self {
+            TargetVersion::IcCdk0_6 => quote!(ic_cdk::spawn(#future)),
+            TargetVersion::IcCdk0_18 => quote!(ic_cdk::futures::spawn(#future)),
+        }
+    }
+
+    pub fn generate_set_timer(&self, delay: TokenStream, callback: TokenStream) -> TokenStream {
+        match self {
+            TargetVersion::IcCdk0_6 => quote!(ic_cdk::timer::set_timer(#delay, #callback)),
+            TargetVersion::IcCdk0_18 => quote!(ic_cdk_timers::set_timer(#delay, #callback)),
+        }
+    }
+
+    pub fn generate_set_timer_interval(
+        &self,
+        interval: TokenStream,
+        callback: TokenStream,
+    ) -> TokenStream {
+        match self {
+            TargetVersion::IcCdk0_6 => {
+                quote!(ic_cdk::timer::set_timer_interval(#interval, #callback))
+            }
+            TargetVersion::IcCdk0_18 => {
+                quote!(ic_cdk_timers::set_timer_interval(#interval, #callback))
+            }
+        }
+    }
+
+    /// The principal of the caller of the current message
+    pub fn generate_caller(&self) -> TokenStream {
+        match self {
+            TargetVersion::IcCdk0_6 => quote!(ic_cdk::api::caller()),
+            TargetVersion::IcCdk0_18 => quote!(ic_cdk::api::msg_caller()),
+        }
+    }
+
+    /// The size in bytes of the current message's arg
+    pub fn generate_arg_data_size(&self) -> TokenStream {
+        match self {
+            TargetVersion::IcCdk0_6 => quote!(ic_cdk::api::call::arg_data_raw_size()),
+            TargetVersion::IcCdk0_18 => quote!(ic_cdk::api::msg_arg_data().len()),
+        }
+    }
+
+    /// The method the current message calls
+    pub fn generate_method_name(&self) -> TokenStream {
+        match self {
+            TargetVersion::IcCdk0_6 => quote!(ic_cdk::api::call::method_name()),
+            TargetVersion::IcCdk0_18 => quote!(ic_cdk::api::msg_method_name()),
+        }
+    }
+
+    /// Accepts the current message in inspect_message
+    pub fn generate_accept_message(&self) -> TokenStream {
+        match self {
+            TargetVersion::IcCdk0_6 => quote!(ic_cdk::api::call::accept_message()),
+            TargetVersion::IcCdk0_18 => quote!(ic_cdk::api::accept_message()),
+        }
+    }
+
+    /// The principal of the generated canister
+    pub fn generate_canister_id(&self) -> TokenStream {
+        match self {
+            TargetVersion::IcCdk0_6 => quote!(ic_cdk::api::id()),
+            TargetVersion::IcCdk0_18 => quote!(ic_cdk::api::canister_self()),
+        }
+    }
+
+    /// A candid record or variant type, where `kind` is `Record` or `Variant`
+    /// and every field is a `candid::types::Field`
+    pub fn generate_compound_type(&self, kind: &str, fields: &[TokenStream]) -> TokenStream {
+        let kind = format_ident!("{}", kind);
+
+        match self {
+            TargetVersion::IcCdk0_6 => quote!(candid::types::Type::#kind(vec![#(#fields),*])),
+            TargetVersion::IcCdk0_18 => {
+                quote!(candid::types::TypeInner::#kind(vec![#(#fields),*]).into())
+            }
+        }
+    }
+
+    /// The crate with `IDLArgs` and the candid text parser
+    pub fn generate_candid_parser_path(&self) -> TokenStream {
+        match self {
+            TargetVersion::IcCdk0_6 => quote!(candid),
+            TargetVersion::IcCdk0_18 => quote!(candid_parser),
+        }
+    }
+}
+
+fn generate_with_cycles(payment: &Payment) -> TokenStream {
+    match payment.to_u128() {
+        Some(cycles) => quote!(.with_cycles(#cycles)),
+        None => quote!(),
+    }
+}
@@ -1,7 +1,11 @@
 pub use try_from_vm_value::generate_try_from_vm_value;
 pub use try_into_vm_value::generate_try_into_vm_value;
+pub use vm_adapter::generate_vm_adapter;
 pub use vm_value_conversion::VmValueConversion;
+pub use vm_value_error::generate_vm_value_error;
 
 pub mod try_from_vm_value;
 pub mod try_into_vm_value;
+pub mod vm_adapter;
 pub mod vm_value_conversion;
+pub mod vm_value_error;
@@ -6,8 +6,5 @@ pub fn generate_try_into_vm_value() -> TokenStream {
         pub trait CdkActTryIntoVmValue<Context, VmValue> {
             fn try_into_vm_value(self, context: Context) -> Result<VmValue, CdkActTryIntoVmValueError>;
         }
-
-        #[derive(Debug)]
-        pub struct CdkActTryIntoVmValueError(pub String);
     }
 }
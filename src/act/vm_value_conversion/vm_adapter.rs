@@ -0,0 +1,558 @@
+use proc_macro2::{Ident, Literal, TokenStream};
+use quote::{format_ident, quote};
+
+use crate::act::node::candid::type_param::TypeParams;
+
+/// Generates the `CdkVmAdapter` trait and the conversions built on it. A CDK
+/// that sets [VmValueConversion::vm_adapter](super::VmValueConversion::vm_adapter)
+/// implements `CdkVmAdapter` for a type wrapping its VM, which describes how to
+/// build and destructure the VM's values, instead of implementing
+/// `CdkActTryIntoVmValue` and `CdkActTryFromVmValue` for every type.
+///
+/// The framework then implements `CdkVmAdapterIntoVmValue` and
+/// `CdkVmAdapterFromVmValue` for the candid primitives, `Vec`, `Option`,
+/// `Box`, and every generated record, variant, tuple, func and service. Any of
+/// those converts with `value.try_into_vm_value(&mut adapter)` and any VM
+/// value with `vm_value.try_from_vm_value(&mut adapter)`. `Vec<u8>` is
+/// converted with the adapter's bytes hooks rather than as an array.
+pub fn generate_vm_adapter(vm_adapter: bool) -> TokenStream {
+    if !vm_adapter {
+        return quote!();
+    }
+
+    let fixed_ints = [
+        quote!(i8),
+        quote!(i16),
+        quote!(i32),
+        quote!(i64),
+        quote!(u16),
+        quote!(u32),
+        quote!(u64),
+    ];
+    let fixed_int_impls = fixed_ints.iter().map(|fixed_int| {
+        quote! {
+            impl<A: CdkVmAdapter> CdkVmAdapterIntoVmValue<A> for #fixed_int {
+                fn into_vm_value(self, adapter: &mut A) -> Result<A::VmValue, CdkActVmValueError> {
+                    adapter.fixed_int_to_vm_value(i128::from(self))
+                }
+            }
+
+            impl<A: CdkVmAdapter> CdkVmAdapterFromVmValue<A> for #fixed_int {
+                fn from_vm_value(value: A::VmValue, adapter: &mut A) -> Result<Self, CdkActVmValueError> {
+                    _cdk_fixed_int_from_i128(adapter.vm_value_to_fixed_int(value)?)
+                }
+            }
+        }
+    });
+
+    quote! {
+        /// Builds and destructures the values of a VM. Every generated type
+        /// converts to and from VM values through these hooks.
+        pub trait CdkVmAdapter {
+            type VmValue;
+
+            fn null_to_vm_value(&mut self) -> Result<Self::VmValue, CdkActVmValueError>;
+            fn bool_to_vm_value(&mut self, value: bool) -> Result<Self::VmValue, CdkActVmValueError>;
+            fn string_to_vm_value(&mut self, value: String) -> Result<Self::VmValue, CdkActVmValueError>;
+            fn int_to_vm_value(&mut self, value: candid::Int) -> Result<Self::VmValue, CdkActVmValueError>;
+            fn nat_to_vm_value(&mut self, value: candid::Nat) -> Result<Self::VmValue, CdkActVmValueError>;
+            /// Every int and nat of a fixed size
+            fn fixed_int_to_vm_value(&mut self, value: i128) -> Result<Self::VmValue, CdkActVmValueError>;
+            /// float32 and float64
+            fn float_to_vm_value(&mut self, value: f64) -> Result<Self::VmValue, CdkActVmValueError>;
+            /// Principals and service references
+            fn principal_to_vm_value(&mut self, value: candid::Principal) -> Result<Self::VmValue, CdkActVmValueError>;
+            fn bytes_to_vm_value(&mut self, value: Vec<u8>) -> Result<Self::VmValue, CdkActVmValueError>;
+            /// Vecs and tuples
+            fn array_to_vm_value(&mut self, values: Vec<Self::VmValue>) -> Result<Self::VmValue, CdkActVmValueError>;
+            fn opt_to_vm_value(&mut self, value: Option<Self::VmValue>) -> Result<Self::VmValue, CdkActVmValueError>;
+            fn record_to_vm_value(&mut self, fields: Vec<(&'static str, Self::VmValue)>) -> Result<Self::VmValue, CdkActVmValueError>;
+            fn variant_to_vm_value(&mut self, case: &'static str, value: Self::VmValue) -> Result<Self::VmValue, CdkActVmValueError>;
+            fn func_to_vm_value(&mut self, principal: candid::Principal, method: String) -> Result<Self::VmValue, CdkActVmValueError>;
+
+            fn vm_value_to_null(&mut self, value: Self::VmValue) -> Result<(), CdkActVmValueError>;
+            fn vm_value_to_bool(&mut self, value: Self::VmValue) -> Result<bool, CdkActVmValueError>;
+            fn vm_value_to_string(&mut self, value: Self::VmValue) -> Result<String, CdkActVmValueError>;
+            fn vm_value_to_int(&mut self, value: Self::VmValue) -> Result<candid::Int, CdkActVmValueError>;
+            fn vm_value_to_nat(&mut self, value: Self::VmValue) -> Result<candid::Nat, CdkActVmValueError>;
+            fn vm_value_to_fixed_int(&mut self, value: Self::VmValue) -> Result<i128, CdkActVmValueError>;
+            fn vm_value_to_float(&mut self, value: Self::VmValue) -> Result<f64, CdkActVmValueError>;
+            fn vm_value_to_principal(&mut self, value: Self::VmValue) -> Result<candid::Principal, CdkActVmValueError>;
+            fn vm_value_to_bytes(&mut self, value: Self::VmValue) -> Result<Vec<u8>, CdkActVmValueError>;
+            fn vm_value_to_array(&mut self, value: Self::VmValue) -> Result<Vec<Self::VmValue>, CdkActVmValueError>;
+            fn vm_value_to_opt(&mut self, value: Self::VmValue) -> Result<Option<Self::VmValue>, CdkActVmValueError>;
+            fn vm_value_to_record_field(&mut self, value: &Self::VmValue, field: &'static str) -> Result<Self::VmValue, CdkActVmValueError>;
+            /// The name of the case and its value
+            fn vm_value_to_variant(&mut self, value: Self::VmValue) -> Result<(String, Self::VmValue), CdkActVmValueError>;
+            fn vm_value_to_func(&mut self, value: Self::VmValue) -> Result<(candid::Principal, String), CdkActVmValueError>;
+        }
+
+        pub trait CdkVmAdapterIntoVmValue<A: CdkVmAdapter>: Sized {
+            fn into_vm_value(self, adapter: &mut A) -> Result<A::VmValue, CdkActVmValueError>;
+
+            /// Converts a `Vec<Self>`, which is an array unless overridden
+            fn vec_into_vm_value(values: Vec<Self>, adapter: &mut A) -> Result<A::VmValue, CdkActVmValueError> {
+                let values = values
+                    .into_iter()
+                    .enumerate()
+                    .map(|(index, value)| {
+                        value
+                            .into_vm_value(adapter)
+                            .map_err(|err| err.in_index(index))
+                    })
+                    .collect::<Result<Vec<_>, _>>()?;
+
+                adapter.array_to_vm_value(values)
+            }
+        }
+
+        pub trait CdkVmAdapterFromVmValue<A: CdkVmAdapter>: Sized {
+            fn from_vm_value(value: A::VmValue, adapter: &mut A) -> Result<Self, CdkActVmValueError>;
+
+            /// Converts to a `Vec<Self>` from an array unless overridden
+            fn vec_from_vm_value(value: A::VmValue, adapter: &mut A) -> Result<Vec<Self>, CdkActVmValueError> {
+                adapter
+                    .vm_value_to_array(value)?
+                    .into_iter()
+                    .enumerate()
+                    .map(|(index, value)| {
+                        Self::from_vm_value(value, adapter).map_err(|err| err.in_index(index))
+                    })
+                    .collect()
+            }
+        }
+
+        impl<'a, A: CdkVmAdapter, T: CdkVmAdapterIntoVmValue<A>> CdkActTryIntoVmValue<&'a mut A, A::VmValue> for T {
+            fn try_into_vm_value(self, adapter: &'a mut A) -> Result<A::VmValue, CdkActVmValueError> {
+                self.into_vm_value(adapter)
+            }
+        }
+
+        impl<'a, A: CdkVmAdapter, T: CdkVmAdapterFromVmValue<A>> CdkActTryFromVmValue<T, CdkActVmValueError, &'a mut A> for A::VmValue {
+            fn try_from_vm_value(self, adapter: &'a mut A) -> Result<T, CdkActVmValueError> {
+                T::from_vm_value(self, adapter)
+            }
+        }
+
+        fn _cdk_fixed_int_from_i128<T: std::convert::TryFrom<i128>>(value: i128) -> Result<T, CdkActVmValueError> {
+            T::try_from(value).map_err(|_| {
+                CdkActVmValueError::out_of_range(std::any::type_name::<T>(), &value.to_string())
+            })
+        }
+
+        impl<A: CdkVmAdapter> CdkVmAdapterIntoVmValue<A> for () {
+            fn into_vm_value(self, adapter: &mut A) -> Result<A::VmValue, CdkActVmValueError> {
+                adapter.null_to_vm_value()
+            }
+        }
+
+        impl<A: CdkVmAdapter> CdkVmAdapterFromVmValue<A> for () {
+            fn from_vm_value(value: A::VmValue, adapter: &mut A) -> Result<Self, CdkActVmValueError> {
+                adapter.vm_value_to_null(value)
+            }
+        }
+
+        impl<A: CdkVmAdapter> CdkVmAdapterIntoVmValue<A> for bool {
+            fn into_vm_value(self, adapter: &mut A) -> Result<A::VmValue, CdkActVmValueError> {
+                adapter.bool_to_vm_value(self)
+            }
+        }
+
+        impl<A: CdkVmAdapter> CdkVmAdapterFromVmValue<A> for bool {
+            fn from_vm_value(value: A::VmValue, adapter: &mut A) -> Result<Self, CdkActVmValueError> {
+                adapter.vm_value_to_bool(value)
+            }
+        }
+
+        impl<A: CdkVmAdapter> CdkVmAdapterIntoVmValue<A> for String {
+            fn into_vm_value(self, adapter: &mut A) -> Result<A::VmValue, CdkActVmValueError> {
+                adapter.string_to_vm_value(self)
+            }
+        }
+
+        impl<A: CdkVmAdapter> CdkVmAdapterFromVmValue<A> for String {
+            fn from_vm_value(value: A::VmValue, adapter: &mut A) -> Result<Self, CdkActVmValueError> {
+                adapter.vm_value_to_string(value)
+            }
+        }
+
+        impl<A: CdkVmAdapter> CdkVmAdapterIntoVmValue<A> for candid::Int {
+            fn into_vm_value(self, adapter: &mut A) -> Result<A::VmValue, CdkActVmValueError> {
+                adapter.int_to_vm_value(self)
+            }
+        }
+
+        impl<A: CdkVmAdapter> CdkVmAdapterFromVmValue<A> for candid::Int {
+            fn from_vm_value(value: A::VmValue, adapter: &mut A) -> Result<Self, CdkActVmValueError> {
+                adapter.vm_value_to_int(value)
+            }
+        }
+
+        impl<A: CdkVmAdapter> CdkVmAdapterIntoVmValue<A> for candid::Nat {
+            fn into_vm_value(self, adapter: &mut A) -> Result<A::VmValue, CdkActVmValueError> {
+                adapter.nat_to_vm_value(self)
+            }
+        }
+
+        impl<A: CdkVmAdapter> CdkVmAdapterFromVmValue<A> for candid::Nat {
+            fn from_vm_value(value: A::VmValue, adapter: &mut A) -> Result<Self, CdkActVmValueError> {
+                adapter.vm_value_to_nat(value)
+            }
+        }
+
+        #(#fixed_int_impls)*
+
+        impl<A: CdkVmAdapter> CdkVmAdapterIntoVmValue<A> for u8 {
+            fn into_vm_value(self, adapter: &mut A) -> Result<A::VmValue, CdkActVmValueError> {
+                adapter.fixed_int_to_vm_value(i128::from(self))
+            }
+
+            fn vec_into_vm_value(values: Vec<Self>, adapter: &mut A) -> Result<A::VmValue, CdkActVmValueError> {
+                adapter.bytes_to_vm_value(values)
+            }
+        }
+
+        impl<A: CdkVmAdapter> CdkVmAdapterFromVmValue<A> for u8 {
+            fn from_vm_value(value: A::VmValue, adapter: &mut A) -> Result<Self, CdkActVmValueError> {
+                _cdk_fixed_int_from_i128(adapter.vm_value_to_fixed_int(value)?)
+            }
+
+            fn vec_from_vm_value(value: A::VmValue, adapter: &mut A) -> Result<Vec<Self>, CdkActVmValueError> {
+                adapter.vm_value_to_bytes(value)
+            }
+        }
+
+        impl<A: CdkVmAdapter> CdkVmAdapterIntoVmValue<A> for _CdkFloat32 {
+            fn into_vm_value(self, adapter: &mut A) -> Result<A::VmValue, CdkActVmValueError> {
+                adapter.float_to_vm_value(f64::from(self.0))
+            }
+        }
+
+        impl<A: CdkVmAdapter> CdkVmAdapterFromVmValue<A> for _CdkFloat32 {
+            fn from_vm_value(value: A::VmValue, adapter: &mut A) -> Result<Self, CdkActVmValueError> {
+                Ok(_CdkFloat32::from(adapter.vm_value_to_float(value)? as f32))
+            }
+        }
+
+        impl<A: CdkVmAdapter> CdkVmAdapterIntoVmValue<A> for _CdkFloat64 {
+            fn into_vm_value(self, adapter: &mut A) -> Result<A::VmValue, CdkActVmValueError> {
+                adapter.float_to_vm_value(self.0)
+            }
+        }
+
+        impl<A: CdkVmAdapter> CdkVmAdapterFromVmValue<A> for _CdkFloat64 {
+            fn from_vm_value(value: A::VmValue, adapter: &mut A) -> Result<Self, CdkActVmValueError> {
+                Ok(_CdkFloat64::from(adapter.vm_value_to_float(value)?))
+            }
+        }
+
+        impl<A: CdkVmAdapter> CdkVmAdapterIntoVmValue<A> for candid::Principal {
+            fn into_vm_value(self, adapter: &mut A) -> Result<A::VmValue, CdkActVmValueError> {
+                adapter.principal_to_vm_value(self)
+            }
+        }
+
+        impl<A: CdkVmAdapter> CdkVmAdapterFromVmValue<A> for candid::Principal {
+            fn from_vm_value(value: A::VmValue, adapter: &mut A) -> Result<Self, CdkActVmValueError> {
+                adapter.vm_value_to_principal(value)
+            }
+        }
+
+        impl<A: CdkVmAdapter> CdkVmAdapterIntoVmValue<A> for candid::Reserved {
+            fn into_vm_value(self, adapter: &mut A) -> Result<A::VmValue, CdkActVmValueError> {
+                adapter.null_to_vm_value()
+            }
+        }
+
+        impl<A: CdkVmAdapter> CdkVmAdapterFromVmValue<A> for candid::Reserved {
+            fn from_vm_value(_: A::VmValue, _: &mut A) -> Result<Self, CdkActVmValueError> {
+                Ok(candid::Reserved)
+            }
+        }
+
+        impl<A: CdkVmAdapter> CdkVmAdapterIntoVmValue<A> for candid::Empty {
+            fn into_vm_value(self, _: &mut A) -> Result<A::VmValue, CdkActVmValueError> {
+                match self {}
+            }
+        }
+
+        impl<A: CdkVmAdapter> CdkVmAdapterFromVmValue<A> for candid::Empty {
+            fn from_vm_value(_: A::VmValue, _: &mut A) -> Result<Self, CdkActVmValueError> {
+                Err(CdkActVmValueError::custom("empty has no values".to_string()))
+            }
+        }
+
+        impl<A: CdkVmAdapter, T: CdkVmAdapterIntoVmValue<A>> CdkVmAdapterIntoVmValue<A> for Vec<T> {
+            fn into_vm_value(self, adapter: &mut A) -> Result<A::VmValue, CdkActVmValueError> {
+                T::vec_into_vm_value(self, adapter)
+            }
+        }
+
+        impl<A: CdkVmAdapter, T: CdkVmAdapterFromVmValue<A>> CdkVmAdapterFromVmValue<A> for Vec<T> {
+            fn from_vm_value(value: A::VmValue, adapter: &mut A) -> Result<Self, CdkActVmValueError> {
+                T::vec_from_vm_value(value, adapter)
+            }
+        }
+
+        impl<A: CdkVmAdapter, T: CdkVmAdapterIntoVmValue<A>> CdkVmAdapterIntoVmValue<A> for Option<T> {
+            fn into_vm_value(self, adapter: &mut A) -> Result<A::VmValue, CdkActVmValueError> {
+                let value = match self {
+                    Some(value) => Some(value.into_vm_value(adapter)?),
+                    None => None,
+                };
+
+                adapter.opt_to_vm_value(value)
+            }
+        }
+
+        impl<A: CdkVmAdapter, T: CdkVmAdapterFromVmValue<A>> CdkVmAdapterFromVmValue<A> for Option<T> {
+            fn from_vm_value(value: A::VmValue, adapter: &mut A) -> Result<Self, CdkActVmValueError> {
+                match adapter.vm_value_to_opt(value)? {
+                    Some(value) => Ok(Some(T::from_vm_value(value, adapter)?)),
+                    None => Ok(None),
+                }
+            }
+        }
+
+        impl<A: CdkVmAdapter, T: CdkVmAdapterIntoVmValue<A>> CdkVmAdapterIntoVmValue<A> for Box<T> {
+            fn into_vm_value(self, adapter: &mut A) -> Result<A::VmValue, CdkActVmValueError> {
+                (*self).into_vm_value(adapter)
+            }
+        }
+
+        impl<A: CdkVmAdapter, T: CdkVmAdapterFromVmValue<A>> CdkVmAdapterFromVmValue<A> for Box<T> {
+            fn from_vm_value(value: A::VmValue, adapter: &mut A) -> Result<Self, CdkActVmValueError> {
+                T::from_vm_value(value, adapter).map(Box::new)
+            }
+        }
+    }
+}
+
+/// The generics and where clauses of the adapter conversion impls of a
+/// generated type. Every type param must convert in the same direction.
+struct ImplGenerics {
+    impl_generics: TokenStream,
+    type_generics: TokenStream,
+    into_where_clause: TokenStream,
+    from_where_clause: TokenStream,
+}
+
+fn generate_impl_generics(type_params: &TypeParams) -> ImplGenerics {
+    let names: Vec<_> = type_params
+        .iter()
+        .map(|type_param| format_ident!("{}", type_param.name))
+        .collect();
+    let where_clause = type_params.get_where_clause_token_stream();
+    let generate_where_clause = |bound: TokenStream| {
+        if names.is_empty() {
+            quote!()
+        } else {
+            quote!(#where_clause, #(#names: #bound),*)
+        }
+    };
+
+    ImplGenerics {
+        impl_generics: quote!(<A: CdkVmAdapter, #(#names),*>),
+        type_generics: type_params.get_type_params_token_stream(),
+        into_where_clause: generate_where_clause(quote!(CdkVmAdapterIntoVmValue<A>)),
+        from_where_clause: generate_where_clause(quote!(CdkVmAdapterFromVmValue<A>)),
+    }
+}
+
+fn generate_impls(
+    type_ident: &Ident,
+    type_params: &TypeParams,
+    into_vm_value_body: TokenStream,
+    from_vm_value_body: TokenStream,
+) -> TokenStream {
+    let ImplGenerics {
+        impl_generics,
+        type_generics,
+        into_where_clause,
+        from_where_clause,
+    } = generate_impl_generics(type_params);
+
+    quote! {
+        impl #impl_generics CdkVmAdapterIntoVmValue<A> for #type_ident #type_generics #into_where_clause {
+            fn into_vm_value(self, adapter: &mut A) -> Result<A::VmValue, CdkActVmValueError> {
+                #into_vm_value_body
+            }
+        }
+
+        impl #impl_generics CdkVmAdapterFromVmValue<A> for #type_ident #type_generics #from_where_clause {
+            fn from_vm_value(value: A::VmValue, adapter: &mut A) -> Result<Self, CdkActVmValueError> {
+                #from_vm_value_body
+            }
+        }
+    }
+}
+
+/// `fields` are the Rust names of the record's fields and their names in the
+/// VM
+pub fn generate_record_impls(
+    record_ident: &Ident,
+    type_params: &TypeParams,
+    fields: &[(Ident, String)],
+) -> TokenStream {
+    let field_idents: Vec<_> = fields.iter().map(|(field_ident, _)| field_ident).collect();
+    let vm_names: Vec<_> = fields.iter().map(|(_, vm_name)| vm_name).collect();
+
+    generate_impls(
+        record_ident,
+        type_params,
+        quote! {
+            let fields = vec![
+                #((
+                    #vm_names,
+                    self.#field_idents
+                        .into_vm_value(adapter)
+                        .map_err(|err| err.in_field(#vm_names))?
+                )),*
+            ];
+
+            adapter.record_to_vm_value(fields)
+        },
+        quote! {
+            Ok(#record_ident {
+                #(#field_idents: adapter
+                    .vm_value_to_record_field(&value, #vm_names)
+                    .and_then(|field| CdkVmAdapterFromVmValue::from_vm_value(field, adapter))
+                    .map_err(|err| err.in_field(#vm_names))?),*
+            })
+        },
+    )
+}
+
+/// `cases` are the Rust names of the variant's cases and their names in the
+/// VM. Cases without a value are null in the VM.
+pub fn generate_variant_impls(
+    variant_ident: &Ident,
+    type_params: &TypeParams,
+    cases: &[(Ident, String, bool)],
+) -> TokenStream {
+    let into_vm_value_arms = cases.iter().map(|(case_ident, vm_name, has_value)| {
+        if *has_value {
+            quote! {
+                #variant_ident::#case_ident(value) => {
+                    let value = value
+                        .into_vm_value(adapter)
+                        .map_err(|err| err.in_case(#vm_name))?;
+                    adapter.variant_to_vm_value(#vm_name, value)
+                }
+            }
+        } else {
+            quote! {
+                #variant_ident::#case_ident => {
+                    let value = adapter.null_to_vm_value()?;
+                    adapter.variant_to_vm_value(#vm_name, value)
+                }
+            }
+        }
+    });
+    let from_vm_value_arms = cases.iter().map(|(case_ident, vm_name, has_value)| {
+        if *has_value {
+            quote! {
+                #vm_name => Ok(#variant_ident::#case_ident(
+                    CdkVmAdapterFromVmValue::from_vm_value(value, adapter)
+                        .map_err(|err| err.in_case(#vm_name))?,
+                ))
+            }
+        } else {
+            quote!(#vm_name => Ok(#variant_ident::#case_ident))
+        }
+    });
+    let variant_name = variant_ident.to_string();
+
+    generate_impls(
+        variant_ident,
+        type_params,
+        quote! {
+            match self {
+                #(#into_vm_value_arms),*
+            }
+        },
+        quote! {
+            let (case, value) = adapter.vm_value_to_variant(value)?;
+
+            match case.as_str() {
+                #(#from_vm_value_arms,)*
+                _ => Err(CdkActVmValueError::unknown_case(#variant_name, &case)),
+            }
+        },
+    )
+}
+
+/// Tuples are arrays in the VM
+pub fn generate_tuple_impls(
+    tuple_ident: &Ident,
+    type_params: &TypeParams,
+    elem_count: usize,
+) -> TokenStream {
+    let indexes: Vec<_> = (0..elem_count).map(Literal::usize_unsuffixed).collect();
+    let from_vm_value_elems = indexes.iter().map(|index| {
+        quote! {
+            CdkVmAdapterFromVmValue::from_vm_value(elems.next().unwrap(), adapter)
+                .map_err(|err| err.in_index(#index))?
+        }
+    });
+    let tuple_name = tuple_ident.to_string();
+
+    generate_impls(
+        tuple_ident,
+        type_params,
+        quote! {
+            let elems = vec![#(
+                self.#indexes
+                    .into_vm_value(adapter)
+                    .map_err(|err| err.in_index(#indexes))?
+            ),*];
+
+            adapter.array_to_vm_value(elems)
+        },
+        quote! {
+            let elems = adapter.vm_value_to_array(value)?;
+
+            if elems.len() != #elem_count {
+                return Err(CdkActVmValueError::wrong_length(
+                    #tuple_name,
+                    #elem_count,
+                    elems.len(),
+                ));
+            }
+
+            let mut elems = elems.into_iter();
+
+            Ok(#tuple_ident(#(#from_vm_value_elems),*))
+        },
+    )
+}
+
+pub fn generate_func_impls(func_ident: &Ident) -> TokenStream {
+    generate_impls(
+        func_ident,
+        &TypeParams(vec![]),
+        quote! {
+            adapter.func_to_vm_value(self.0.principal, self.0.method)
+        },
+        quote! {
+            let (principal, method) = adapter.vm_value_to_func(value)?;
+
+            Ok(#func_ident(candid::Func { principal, method }))
+        },
+    )
+}
+
+pub fn generate_service_impls(service_ident: &Ident) -> TokenStream {
+    generate_impls(
+        service_ident,
+        &TypeParams(vec![]),
+        quote! {
+            adapter.principal_to_vm_value(self.0.principal)
+        },
+        quote! {
+            let principal = adapter.vm_value_to_principal(value)?;
+
+            Ok(#service_ident(candid::Service { principal }))
+        },
+    )
+}
@@ -3,4 +3,8 @@ use proc_macro2::TokenStream;
 pub struct VmValueConversion {
     pub try_from_vm_value_impls: TokenStream,
     pub try_into_vm_value_impls: TokenStream,
+    /// Generates every conversion from a `CdkVmAdapter` implemented by the
+    /// CDK instead of deriving `CdkActTryIntoVmValue` and
+    /// `CdkActTryFromVmValue`. See [generate_vm_adapter](super::generate_vm_adapter).
+    pub vm_adapter: bool,
 }
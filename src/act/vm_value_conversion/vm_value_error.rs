@@ -0,0 +1,215 @@
+use proc_macro2::TokenStream;
+use quote::quote;
+
+/// Generates `CdkActVmValueError`, the error of every vm value conversion.
+/// Besides a message it has the kind of failure, the expected candid type,
+/// the actual VM type, and the path to the failing value, like
+/// `arg[1].owner.subaccount[3]`. Conversions build the path from the inside
+/// out by calling `in_field`, `in_case`, `in_index` and `in_arg` on errors as
+/// they return through records, variants, tuples, vecs and params.
+///
+/// `CdkActTryIntoVmValueError` and `CdkActTryFromVmValueError` are aliases of
+/// it, and `CdkActTryIntoVmValueError(message)` still creates a custom error.
+/// CDKs that implement the conversions themselves instead of through the
+/// adapter extend the path on results with `CdkActVmValueResult`.
+pub fn generate_vm_value_error() -> TokenStream {
+    quote! {
+        #[derive(Clone, Debug, PartialEq, Eq)]
+        pub enum CdkActVmValueErrorKind {
+            /// The VM value isn't of the expected type
+            TypeMismatch,
+            /// The number doesn't fit the expected type
+            OutOfRange,
+            /// A record field is missing
+            MissingField,
+            /// The case isn't part of the variant
+            UnknownCase,
+            /// A tuple has the wrong number of elements
+            WrongLength,
+            Custom,
+        }
+
+        #[derive(Clone, Debug, PartialEq, Eq)]
+        pub enum CdkActVmValuePathSegment {
+            Arg(usize),
+            Field(String),
+            Case(String),
+            Index(usize),
+        }
+
+        #[derive(Clone, Debug)]
+        pub struct CdkActVmValueError {
+            pub kind: CdkActVmValueErrorKind,
+            /// The expected candid type
+            pub expected: Option<String>,
+            /// The type of the VM value
+            pub actual: Option<String>,
+            /// From the outermost value to the failing one
+            pub path: Vec<CdkActVmValuePathSegment>,
+            pub message: String,
+        }
+
+        pub type CdkActTryIntoVmValueError = CdkActVmValueError;
+        pub type CdkActTryFromVmValueError = CdkActVmValueError;
+
+        /// Keeps `CdkActTryIntoVmValueError(message)` working now that the
+        /// error is structured
+        #[allow(non_snake_case)]
+        pub fn CdkActTryIntoVmValueError(message: String) -> CdkActVmValueError {
+            CdkActVmValueError::custom(message)
+        }
+
+        impl CdkActVmValueError {
+            pub fn new(kind: CdkActVmValueErrorKind, message: String) -> Self {
+                CdkActVmValueError {
+                    kind,
+                    expected: None,
+                    actual: None,
+                    path: vec![],
+                    message,
+                }
+            }
+
+            pub fn custom(message: String) -> Self {
+                CdkActVmValueError::new(CdkActVmValueErrorKind::Custom, message)
+            }
+
+            pub fn type_mismatch(expected: &str, actual: &str) -> Self {
+                CdkActVmValueError {
+                    expected: Some(expected.to_string()),
+                    actual: Some(actual.to_string()),
+                    ..CdkActVmValueError::new(
+                        CdkActVmValueErrorKind::TypeMismatch,
+                        format!("expected {} but got {}", expected, actual),
+                    )
+                }
+            }
+
+            pub fn out_of_range(expected: &str, value: &str) -> Self {
+                CdkActVmValueError {
+                    expected: Some(expected.to_string()),
+                    ..CdkActVmValueError::new(
+                        CdkActVmValueErrorKind::OutOfRange,
+                        format!("{} is out of range for {}", value, expected),
+                    )
+                }
+            }
+
+            pub fn missing_field(field: &str) -> Self {
+                CdkActVmValueError::new(
+                    CdkActVmValueErrorKind::MissingField,
+                    format!("missing field {}", field),
+                )
+            }
+
+            pub fn unknown_case(variant: &str, case: &str) -> Self {
+                CdkActVmValueError {
+                    expected: Some(variant.to_string()),
+                    ..CdkActVmValueError::new(
+                        CdkActVmValueErrorKind::UnknownCase,
+                        format!("{} is not a case of {}", case, variant),
+                    )
+                }
+            }
+
+            pub fn wrong_length(expected: &str, expected_length: usize, length: usize) -> Self {
+                CdkActVmValueError {
+                    expected: Some(expected.to_string()),
+                    ..CdkActVmValueError::new(
+                        CdkActVmValueErrorKind::WrongLength,
+                        format!(
+                            "{} expects {} elements but got {}",
+                            expected, expected_length, length
+                        ),
+                    )
+                }
+            }
+
+            fn in_segment(mut self, segment: CdkActVmValuePathSegment) -> Self {
+                self.path.insert(0, segment);
+                self
+            }
+
+            pub fn in_arg(self, index: usize) -> Self {
+                self.in_segment(CdkActVmValuePathSegment::Arg(index))
+            }
+
+            pub fn in_field(self, field: &str) -> Self {
+                self.in_segment(CdkActVmValuePathSegment::Field(field.to_string()))
+            }
+
+            pub fn in_case(self, case: &str) -> Self {
+                self.in_segment(CdkActVmValuePathSegment::Case(case.to_string()))
+            }
+
+            pub fn in_index(self, index: usize) -> Self {
+                self.in_segment(CdkActVmValuePathSegment::Index(index))
+            }
+
+            /// The path as text, like `arg[1].owner.subaccount[3]`
+            pub fn path_to_string(&self) -> String {
+                self.path
+                    .iter()
+                    .enumerate()
+                    .map(|(position, segment)| match segment {
+                        CdkActVmValuePathSegment::Arg(index) => format!("arg[{}]", index),
+                        CdkActVmValuePathSegment::Field(name)
+                        | CdkActVmValuePathSegment::Case(name) => {
+                            if position == 0 {
+                                name.clone()
+                            } else {
+                                format!(".{}", name)
+                            }
+                        }
+                        CdkActVmValuePathSegment::Index(index) => format!("[{}]", index),
+                    })
+                    .collect()
+            }
+        }
+
+        /// Extends the path of a failed conversion, e.g.
+        /// `value.try_from_vm_value(context).in_field("owner")?`
+        pub trait CdkActVmValueResult {
+            fn in_arg(self, index: usize) -> Self;
+            fn in_field(self, field: &str) -> Self;
+            fn in_case(self, case: &str) -> Self;
+            fn in_index(self, index: usize) -> Self;
+        }
+
+        impl<T> CdkActVmValueResult for Result<T, CdkActVmValueError> {
+            fn in_arg(self, index: usize) -> Self {
+                self.map_err(|err| err.in_arg(index))
+            }
+
+            fn in_field(self, field: &str) -> Self {
+                self.map_err(|err| err.in_field(field))
+            }
+
+            fn in_case(self, case: &str) -> Self {
+                self.map_err(|err| err.in_case(case))
+            }
+
+            fn in_index(self, index: usize) -> Self {
+                self.map_err(|err| err.in_index(index))
+            }
+        }
+
+        impl From<String> for CdkActVmValueError {
+            fn from(message: String) -> Self {
+                CdkActVmValueError::custom(message)
+            }
+        }
+
+        impl std::fmt::Display for CdkActVmValueError {
+            fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+                if self.path.is_empty() {
+                    write!(f, "{}", self.message)
+                } else {
+                    write!(f, "{}: {}", self.path_to_string(), self.message)
+                }
+            }
+        }
+
+        impl std::error::Error for CdkActVmValueError {}
+    }
+}
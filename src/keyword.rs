@@ -2,6 +2,8 @@ use proc_macro2::{Ident, TokenStream};
 use quote::quote;
 use regex::Regex;
 
+use crate::label;
+
 const RUST_KEYWORDS: [&str; 51] = [
     "abstract", "as", "async", "await", "become", "box", "break", "const", "continue", "crate",
     "do", "dyn", "else", "enum", "extern", "false", "final", "fn", "for", "if", "impl", "in",
@@ -10,6 +12,76 @@ const RUST_KEYWORDS: [&str; 51] = [
     "typeof", "unsafe", "unsized", "use", "virtual", "where", "while", "yield",
 ];
 
+/// Type names that the generated code relies on. A user defined type with one
+/// of these names would shadow it and break the generated code.
+const RESERVED_TYPE_NAMES: [&str; 58] = [
+    "Box",
+    "Cell",
+    "Clone",
+    "Copy",
+    "Debug",
+    "Default",
+    "Deserialize",
+    "Empty",
+    "Eq",
+    "Err",
+    "From",
+    "Func",
+    "Hash",
+    "Int",
+    "Into",
+    "Iterator",
+    "Nat",
+    "None",
+    "Ok",
+    "Option",
+    "Ord",
+    "PartialEq",
+    "PartialOrd",
+    "Principal",
+    "RefCell",
+    "Reserved",
+    "Result",
+    "Send",
+    "Service",
+    "Sized",
+    "Some",
+    "String",
+    "Sync",
+    "ToString",
+    "TryFrom",
+    "TryInto",
+    "Vec",
+    "bool",
+    "candid",
+    "char",
+    "core",
+    "f32",
+    "f64",
+    "i128",
+    "i16",
+    "i32",
+    "i64",
+    "i8",
+    "isize",
+    "serde",
+    "std",
+    "str",
+    "u128",
+    "u16",
+    "u32",
+    "u64",
+    "u8",
+    "usize",
+];
+
+/// Types declared by the generated code itself are prefixed with these
+const GENERATED_TYPE_NAME_PREFIXES: [&str; 2] = ["_Cdk", "CdkAct"];
+
+/// User defined type names that collide with a keyword or with a name the
+/// generated code relies on are prefixed with this
+pub const TYPE_NAME_PREFIX: &str = "_CdkType";
+
 /// Why do we need this?
 ///
 /// All of the languages that need to be serialized into candid have different
@@ -60,9 +132,17 @@ const RUST_KEYWORDS: [&str; 51] = [
 /// implementation of the derive_try_from_vm_value and derive_try_into_vm_value
 /// for structs and enums. They will need to use the [restore_for_vm] function
 /// to handle any potential keyword conversions.
+///
+/// Names that aren't valid Rust identifiers at all, such as "content-type",
+/// are encoded by the [label] module instead. All three functions handle those
+/// names as well, so callers don't need to tell them apart.
 
 pub fn generate_rename_attribute(name: &Ident, keyword_list: &Vec<String>) -> TokenStream {
-    let restored_keyword = restore_for_serde(&name.to_string(), keyword_list);
+    let restored_keyword = if label::is_encoded(&name.to_string()) {
+        label::to_candid_label(&name.to_string())
+    } else {
+        restore_for_serde(&name.to_string(), keyword_list)
+    };
     if restored_keyword == name.to_string() {
         quote!()
     } else {
@@ -77,8 +157,12 @@ pub fn generate_rename_attribute(name: &Ident, keyword_list: &Vec<String>) -> To
 // it rust safe by appending one additional underscore.
 // But if it matches any language specific keywords then we shouldn't add
 // another one.
+// Names that aren't valid Rust identifiers are encoded instead.
 pub fn make_rust_safe(name: &String, keywords: &Vec<String>) -> String {
-    if !is_language_safe_keyword(name, keywords) && is_rust_unsafe_keyword(name) {
+    let identifier = label::to_rust_identifier(name);
+    if label::is_encoded(&identifier) {
+        identifier
+    } else if !is_language_safe_keyword(name, keywords) && is_rust_unsafe_keyword(name) {
         format!("{name}_")
     } else {
         name.clone()
@@ -88,8 +172,11 @@ pub fn make_rust_safe(name: &String, keywords: &Vec<String>) -> String {
 // Remove the ending underscore if the name is a rust keyword and it isn't a
 // language specific keyword. If the name is a language specific keyword then
 // the vm will need the underscore and we must not remove it.
+// Encoded names are decoded back to the original label.
 pub fn restore_for_vm(name: &String, keywords: &Vec<String>) -> String {
-    if !is_language_safe_keyword(name, keywords) && is_rust_safe_keyword(name) {
+    if label::is_encoded(name) {
+        label::to_candid_label(name)
+    } else if !is_language_safe_keyword(name, keywords) && is_rust_safe_keyword(name) {
         name[..name.len() - 1].to_string()
     } else {
         name.clone()
@@ -138,3 +225,21 @@ fn to_unsafe_regex(name: &String) -> Regex {
     let name = regex::escape(name);
     Regex::new(format!(r#"^{name}_*$"#).as_str()).unwrap()
 }
+
+// Any type name that is a rust keyword, one of the names the generated code
+// relies on, or that could collide with a type the generated code declares is
+// prefixed with [TYPE_NAME_PREFIX]. Only the Rust name changes. The candid
+// file is written from the ACT and keeps the declared name.
+pub fn make_type_name_rust_safe(name: &String) -> String {
+    let is_reserved = RUST_KEYWORDS.contains(&name.as_str())
+        || RESERVED_TYPE_NAMES.contains(&name.as_str())
+        || GENERATED_TYPE_NAME_PREFIXES
+            .iter()
+            .any(|prefix| name.starts_with(prefix));
+
+    if is_reserved {
+        format!("{TYPE_NAME_PREFIX}{name}")
+    } else {
+        name.clone()
+    }
+}
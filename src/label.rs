@@ -0,0 +1,130 @@
+/// Why do we need this?
+///
+/// Candid labels can be any text, such as "content-type", "2fa" or a unicode
+/// name, but the records and variants that we generate need every label to be
+/// a valid Rust identifier. Rust keywords are handled by the [keyword](crate::keyword)
+/// module, everything else that isn't a valid identifier is handled here.
+///
+/// How does it work?
+///
+/// Labels that are already valid identifiers are used as is. Any other label
+/// is encoded into an identifier starting with [LABEL_PREFIX]. ASCII letters
+/// and digits are kept, an underscore is written as two underscores, and any
+/// other character is written as its hex code point between two underscores.
+/// For example "content-type" becomes "_cdk_label_content_2d_type". The
+/// encoding can be reversed with [to_candid_label], so the encoded identifier
+/// is itself the mapping back to the original label. Labels that happen to
+/// start with [LABEL_PREFIX] are encoded too, so two different labels can
+/// never produce the same identifier.
+///
+/// Numeric labels and labels that are only known by their hash are named by
+/// [from_label_id], e.g. "_42_". That name carries no label of its own: the
+/// member keeps its id in [label_id](crate::act::node::Member::label_id) and
+/// the record or variant gets hand-written candid impls that use the id (see
+/// [candid_impls](crate::act::node::candid::candid_impls)). Text labels of that
+/// shape are encoded like any other invalid label so that they can't collide
+/// with a label id.
+///
+/// The record and variant members for encoded labels get a serde rename
+/// attribute so that the original label is used in candid, and
+/// [restore_for_vm](crate::keyword::restore_for_vm) decodes them so that the
+/// vm sees the original label as well.
+pub const LABEL_PREFIX: &str = "_cdk_label_";
+
+/// Maps any candid label to a valid Rust identifier
+pub fn to_rust_identifier(label: &str) -> String {
+    if is_valid_identifier(label) {
+        return label.to_string();
+    }
+
+    let encoded: String = label
+        .chars()
+        .map(|c| match c {
+            '_' => "__".to_string(),
+            c if c.is_ascii_alphanumeric() => c.to_string(),
+            c => format!("_{:x}_", c as u32),
+        })
+        .collect();
+
+    format!("{LABEL_PREFIX}{encoded}")
+}
+
+/// Recovers the original candid label from an identifier created by
+/// [to_rust_identifier]. Identifiers that weren't encoded are returned as is.
+pub fn to_candid_label(identifier: &str) -> String {
+    let encoded = match identifier.strip_prefix(LABEL_PREFIX) {
+        Some(encoded) => encoded,
+        None => return identifier.to_string(),
+    };
+
+    let mut label = String::new();
+    let mut chars = encoded.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        if c != '_' {
+            label.push(c);
+            continue;
+        }
+
+        if chars.peek() == Some(&'_') {
+            chars.next();
+            label.push('_');
+            continue;
+        }
+
+        let code_point: String = chars.by_ref().take_while(|c| *c != '_').collect();
+        match u32::from_str_radix(&code_point, 16)
+            .ok()
+            .and_then(char::from_u32)
+        {
+            Some(c) => label.push(c),
+            None => return identifier.to_string(),
+        }
+    }
+
+    label
+}
+
+/// Names the member of a numeric or hashed candid label id. The name is only
+/// an identifier, the id itself is kept by the member.
+pub fn from_label_id(id: u32) -> String {
+    format!("_{id}_")
+}
+
+/// The id that candid uses for a text label on the wire
+pub fn hash_label(label: &str) -> u32 {
+    label.bytes().fold(0, |hash: u32, byte| {
+        hash.wrapping_mul(223).wrapping_add(byte as u32)
+    })
+}
+
+/// Whether the identifier was created by [to_rust_identifier] from a label
+/// that isn't a valid Rust identifier
+pub fn is_encoded(identifier: &str) -> bool {
+    identifier.starts_with(LABEL_PREFIX)
+}
+
+fn is_valid_identifier(label: &str) -> bool {
+    let mut chars = label.chars();
+    let starts_validly = match chars.next() {
+        Some(first) => first.is_ascii_alphabetic() || first == '_',
+        None => false,
+    };
+
+    starts_validly
+        && label != "_"
+        && chars.all(|c| c.is_ascii_alphanumeric() || c == '_')
+        && !label.starts_with(LABEL_PREFIX)
+        && !is_label_id_shaped(label)
+}
+
+/// Whether the label looks like a name created by [from_label_id]
+fn is_label_id_shaped(label: &str) -> bool {
+    match label
+        .strip_prefix('_')
+        .and_then(|rest| rest.strip_suffix('_'))
+    {
+        Some(digits) => !digits.is_empty() && digits.chars().all(|c| c.is_ascii_digit()),
+        None => false,
+    }
+}
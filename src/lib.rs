@@ -1,5 +1,6 @@
 pub mod act;
 pub mod keyword;
+pub mod label;
 pub mod traits;
 pub mod utils;
 
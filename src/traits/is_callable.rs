@@ -4,6 +4,7 @@ use quote::quote;
 use crate::{
     act::{
         node::{node_parts::mode::Mode, Context, Param, ReturnType},
+        target_version::TargetVersion,
         Declaration,
     },
     traits::{HasInlineName, ToTypeAnnotation},
@@ -47,6 +48,11 @@ pub trait IsCallable {
             .to_type_annotation(context, function_name.to_string());
         let func_mode = match mode {
             Mode::Query => quote!(query),
+            // candid 0.8 has no composite queries
+            Mode::CompositeQuery => match context.target_version {
+                TargetVersion::IcCdk0_6 => quote!(query),
+                TargetVersion::IcCdk0_18 => quote!(composite_query),
+            },
             Mode::Oneway => quote!(oneway),
             Mode::Update => quote!(),
         };
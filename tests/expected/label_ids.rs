@@ -0,0 +1,291 @@
+#[derive(Debug, Clone, PartialEq)]
+struct LabeledRecord {
+    _100_: Box<Option<candid::Nat>>,
+    name: Box<String>,
+    _0_: Box<candid::Nat>,
+    _42_: Box<String>,
+}
+impl candid::CandidType for LabeledRecord {
+    fn _ty() -> candid::types::Type {
+        candid::types::TypeInner::Record(vec![
+            candid::types::Field {
+                id: candid::types::Label::Id(0u32).into(),
+                ty: <Box<candid::Nat> as candid::CandidType>::ty(),
+            },
+            candid::types::Field {
+                id: candid::types::Label::Id(42u32).into(),
+                ty: <Box<String> as candid::CandidType>::ty(),
+            },
+            candid::types::Field {
+                id: candid::types::Label::Id(100u32).into(),
+                ty: <Box<Option<candid::Nat>> as candid::CandidType>::ty(),
+            },
+            candid::types::Field {
+                id: candid::types::Label::Named("name".to_string()).into(),
+                ty: <Box<String> as candid::CandidType>::ty(),
+            },
+        ])
+        .into()
+    }
+    fn id() -> candid::types::TypeId {
+        candid::types::TypeId::of::<Self>()
+    }
+    fn idl_serialize<S>(&self, serializer: S) -> Result<(), S::Error>
+    where
+        S: candid::types::Serializer,
+    {
+        let mut compound = serializer.serialize_struct()?;
+        candid::types::Compound::serialize_element(&mut compound, &self._0_)?;
+        candid::types::Compound::serialize_element(&mut compound, &self._42_)?;
+        candid::types::Compound::serialize_element(&mut compound, &self._100_)?;
+        candid::types::Compound::serialize_element(&mut compound, &self.name)?;
+        Ok(())
+    }
+}
+impl<'de> serde::Deserialize<'de> for LabeledRecord {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        enum _CdkFieldKey {
+            Field0,
+            Field1,
+            Field2,
+            Field3,
+            Ignored,
+        }
+        struct _CdkFieldKeyVisitor;
+        impl<'de> serde::de::Visitor<'de> for _CdkFieldKeyVisitor {
+            type Value = _CdkFieldKey;
+            fn expecting(&self, formatter: &mut std::fmt::Formatter) -> std::fmt::Result {
+                formatter.write_str("a candid label")
+            }
+            fn visit_u64<E>(self, value: u64) -> Result<Self::Value, E>
+            where
+                E: serde::de::Error,
+            {
+                Ok(match value {
+                    0 => _CdkFieldKey::Field0,
+                    42 => _CdkFieldKey::Field1,
+                    100 => _CdkFieldKey::Field2,
+                    1224700491 => _CdkFieldKey::Field3,
+                    _ => _CdkFieldKey::Ignored,
+                })
+            }
+            fn visit_str<E>(self, value: &str) -> Result<Self::Value, E>
+            where
+                E: serde::de::Error,
+            {
+                Ok(match value {
+                    "0" => _CdkFieldKey::Field0,
+                    "42" => _CdkFieldKey::Field1,
+                    "100" => _CdkFieldKey::Field2,
+                    "name" => _CdkFieldKey::Field3,
+                    _ => _CdkFieldKey::Ignored,
+                })
+            }
+        }
+        impl<'de> serde::Deserialize<'de> for _CdkFieldKey {
+            fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+            where
+                D: serde::Deserializer<'de>,
+            {
+                serde::Deserializer::deserialize_identifier(deserializer, _CdkFieldKeyVisitor)
+            }
+        }
+        struct _CdkVisitor<'de> {
+            marker: std::marker::PhantomData<LabeledRecord>,
+            lifetime: std::marker::PhantomData<&'de ()>,
+        }
+        impl<'de> serde::de::Visitor<'de> for _CdkVisitor<'de> {
+            type Value = LabeledRecord;
+            fn expecting(&self, formatter: &mut std::fmt::Formatter) -> std::fmt::Result {
+                formatter.write_str(stringify!(LabeledRecord))
+            }
+            fn visit_map<A>(self, mut map: A) -> Result<Self::Value, A::Error>
+            where
+                A: serde::de::MapAccess<'de>,
+            {
+                let mut field_0: Option<Box<candid::Nat>> = None;
+                let mut field_1: Option<Box<String>> = None;
+                let mut field_2: Option<Box<Option<candid::Nat>>> = None;
+                let mut field_3: Option<Box<String>> = None;
+                while let Some(key) = serde::de::MapAccess::next_key::<_CdkFieldKey>(&mut map)? {
+                    match key {
+                        _CdkFieldKey::Field0 => {
+                            field_0 = Some(serde::de::MapAccess::next_value(&mut map)?);
+                        }
+                        _CdkFieldKey::Field1 => {
+                            field_1 = Some(serde::de::MapAccess::next_value(&mut map)?);
+                        }
+                        _CdkFieldKey::Field2 => {
+                            field_2 = Some(serde::de::MapAccess::next_value(&mut map)?);
+                        }
+                        _CdkFieldKey::Field3 => {
+                            field_3 = Some(serde::de::MapAccess::next_value(&mut map)?);
+                        }
+                        _CdkFieldKey::Ignored => {
+                            serde::de::MapAccess::next_value::<serde::de::IgnoredAny>(&mut map)?;
+                        }
+                    }
+                }
+                Ok(LabeledRecord {
+                    _0_: field_0.ok_or_else(|| serde::de::Error::missing_field("_0_"))?,
+                    _42_: field_1.ok_or_else(|| serde::de::Error::missing_field("_42_"))?,
+                    _100_: field_2.ok_or_else(|| serde::de::Error::missing_field("_100_"))?,
+                    name: field_3.ok_or_else(|| serde::de::Error::missing_field("name"))?,
+                })
+            }
+        }
+        let visitor = _CdkVisitor {
+            marker: std::marker::PhantomData,
+            lifetime: std::marker::PhantomData,
+        };
+        serde::Deserializer::deserialize_struct(
+            deserializer,
+            "LabeledRecord",
+            &["_0_", "_42_", "_100_", "name"],
+            visitor,
+        )
+    }
+}
+#[derive(Debug, Clone, PartialEq)]
+enum LabeledVariant {
+    other(candid::Nat),
+    _7_(String),
+    _1_(()),
+}
+impl candid::CandidType for LabeledVariant {
+    fn _ty() -> candid::types::Type {
+        candid::types::TypeInner::Variant(vec![
+            candid::types::Field {
+                id: candid::types::Label::Id(1u32).into(),
+                ty: <() as candid::CandidType>::ty(),
+            },
+            candid::types::Field {
+                id: candid::types::Label::Id(7u32).into(),
+                ty: <String as candid::CandidType>::ty(),
+            },
+            candid::types::Field {
+                id: candid::types::Label::Named("other".to_string()).into(),
+                ty: <candid::Nat as candid::CandidType>::ty(),
+            },
+        ])
+        .into()
+    }
+    fn id() -> candid::types::TypeId {
+        candid::types::TypeId::of::<Self>()
+    }
+    fn idl_serialize<S>(&self, serializer: S) -> Result<(), S::Error>
+    where
+        S: candid::types::Serializer,
+    {
+        match *self {
+            LabeledVariant::_1_(ref value) => {
+                let mut compound = serializer.serialize_variant(0u64)?;
+                candid::types::Compound::serialize_element(&mut compound, value)?;
+            }
+            LabeledVariant::_7_(ref value) => {
+                let mut compound = serializer.serialize_variant(1u64)?;
+                candid::types::Compound::serialize_element(&mut compound, value)?;
+            }
+            LabeledVariant::other(ref value) => {
+                let mut compound = serializer.serialize_variant(2u64)?;
+                candid::types::Compound::serialize_element(&mut compound, value)?;
+            }
+        }
+        Ok(())
+    }
+}
+impl<'de> serde::Deserialize<'de> for LabeledVariant {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        enum _CdkFieldKey {
+            Field0,
+            Field1,
+            Field2,
+            Ignored,
+        }
+        struct _CdkFieldKeyVisitor;
+        impl<'de> serde::de::Visitor<'de> for _CdkFieldKeyVisitor {
+            type Value = _CdkFieldKey;
+            fn expecting(&self, formatter: &mut std::fmt::Formatter) -> std::fmt::Result {
+                formatter.write_str("a candid label")
+            }
+            fn visit_u64<E>(self, value: u64) -> Result<Self::Value, E>
+            where
+                E: serde::de::Error,
+            {
+                Ok(match value {
+                    1 => _CdkFieldKey::Field0,
+                    7 => _CdkFieldKey::Field1,
+                    913729232 => _CdkFieldKey::Field2,
+                    _ => _CdkFieldKey::Ignored,
+                })
+            }
+            fn visit_str<E>(self, value: &str) -> Result<Self::Value, E>
+            where
+                E: serde::de::Error,
+            {
+                Ok(match value {
+                    "1" => _CdkFieldKey::Field0,
+                    "7" => _CdkFieldKey::Field1,
+                    "other" => _CdkFieldKey::Field2,
+                    _ => _CdkFieldKey::Ignored,
+                })
+            }
+        }
+        impl<'de> serde::Deserialize<'de> for _CdkFieldKey {
+            fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+            where
+                D: serde::Deserializer<'de>,
+            {
+                serde::Deserializer::deserialize_identifier(deserializer, _CdkFieldKeyVisitor)
+            }
+        }
+        struct _CdkVisitor<'de> {
+            marker: std::marker::PhantomData<LabeledVariant>,
+            lifetime: std::marker::PhantomData<&'de ()>,
+        }
+        impl<'de> serde::de::Visitor<'de> for _CdkVisitor<'de> {
+            type Value = LabeledVariant;
+            fn expecting(&self, formatter: &mut std::fmt::Formatter) -> std::fmt::Result {
+                formatter.write_str(stringify!(LabeledVariant))
+            }
+            fn visit_enum<A>(self, data: A) -> Result<Self::Value, A::Error>
+            where
+                A: serde::de::EnumAccess<'de>,
+            {
+                match serde::de::EnumAccess::variant::<_CdkFieldKey>(data)? {
+                    (_CdkFieldKey::Field0, variant) => {
+                        serde::de::VariantAccess::newtype_variant::<()>(variant)
+                            .map(LabeledVariant::_1_)
+                    }
+                    (_CdkFieldKey::Field1, variant) => {
+                        serde::de::VariantAccess::newtype_variant::<String>(variant)
+                            .map(LabeledVariant::_7_)
+                    }
+                    (_CdkFieldKey::Field2, variant) => {
+                        serde::de::VariantAccess::newtype_variant::<candid::Nat>(variant)
+                            .map(LabeledVariant::other)
+                    }
+                    (_CdkFieldKey::Ignored, _) => {
+                        Err(serde::de::Error::custom("unknown variant case"))
+                    }
+                }
+            }
+        }
+        let visitor = _CdkVisitor {
+            marker: std::marker::PhantomData,
+            lifetime: std::marker::PhantomData,
+        };
+        serde::Deserializer::deserialize_enum(
+            deserializer,
+            "LabeledVariant",
+            &["_1_", "_7_", "other"],
+            visitor,
+        )
+    }
+}
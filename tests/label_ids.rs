@@ -0,0 +1,191 @@
+// The expected impls are generated code, which doesn't follow the naming
+// and boxing lints
+#![allow(non_camel_case_types, clippy::box_collection)]
+
+use std::str::FromStr;
+
+use candid::{
+    types::value::{IDLField, IDLValue},
+    types::Label,
+    Decode, Encode, IDLArgs, Nat,
+};
+use cdk_framework::act::{
+    interfaces::builders::{case, member, opt, primitive, record, variant},
+    node::{candid::Primitive, CandidType, Context, Member},
+    target_version::TargetVersion,
+    Declare,
+};
+use proc_macro2::TokenStream;
+use quote::quote;
+
+include!("expected/label_ids.rs");
+
+fn label_id(id: u32, candid_type: CandidType) -> Member {
+    Member {
+        label_id: Some(id),
+        ..member(&id.to_string(), candid_type)
+    }
+}
+
+fn generate_declarations() -> TokenStream {
+    let context = Context {
+        keyword_list: vec![],
+        cdk_name: "test".to_string(),
+        default_derives: vec![
+            quote!(serde::Deserialize),
+            quote!(Debug),
+            quote!(candid::CandidType),
+            quote!(Clone),
+            quote!(PartialEq),
+        ],
+        default_attributes: vec![],
+        certification: None,
+        vm_adapter: false,
+        target_version: TargetVersion::IcCdk0_18,
+    };
+    let labeled_record = record(
+        Some("LabeledRecord"),
+        vec![
+            label_id(100, opt(primitive(Primitive::Nat))),
+            member("name", primitive(Primitive::String)),
+            label_id(0, primitive(Primitive::Nat)),
+            label_id(42, primitive(Primitive::String)),
+        ],
+    )
+    .to_declaration(&context, String::new());
+    let labeled_variant = variant(
+        Some("LabeledVariant"),
+        vec![
+            member("other", primitive(Primitive::Nat)),
+            label_id(7, primitive(Primitive::String)),
+            Member {
+                label_id: Some(1),
+                ..case("1")
+            },
+        ],
+    )
+    .to_declaration(&context, String::new());
+
+    quote!(#labeled_record #labeled_variant)
+}
+
+#[test]
+fn generates_the_expected_impls() {
+    let expected = TokenStream::from_str(include_str!("expected/label_ids.rs")).unwrap();
+
+    assert_eq!(normalize(&generate_declarations()), normalize(&expected));
+}
+
+#[test]
+fn record_round_trips_with_numeric_and_sparse_label_ids() {
+    let labeled_record = LabeledRecord {
+        _0_: Box::new(Nat::from(7u32)),
+        _42_: Box::new("forty-two".to_string()),
+        _100_: Box::new(Some(Nat::from(100u32))),
+        name: Box::new("name".to_string()),
+    };
+
+    let bytes = Encode!(&labeled_record).unwrap();
+    let args = IDLArgs::from_bytes(&bytes).unwrap();
+    let expected_value = IDLValue::Record(vec![
+        IDLField {
+            id: Label::Id(0),
+            val: IDLValue::Nat(Nat::from(7u32)),
+        },
+        IDLField {
+            id: Label::Id(42),
+            val: IDLValue::Text("forty-two".to_string()),
+        },
+        IDLField {
+            id: Label::Id(100),
+            val: IDLValue::Opt(Box::new(IDLValue::Nat(Nat::from(100u32)))),
+        },
+        IDLField {
+            id: Label::Id(label_hash("name")),
+            val: IDLValue::Text("name".to_string()),
+        },
+    ]);
+
+    assert_eq!(args.args, vec![expected_value.clone()]);
+    assert_eq!(
+        Decode!(&bytes, LabeledRecord).unwrap(),
+        labeled_record.clone()
+    );
+
+    let bytes = IDLArgs::new(&[expected_value]).to_bytes().unwrap();
+
+    assert_eq!(Decode!(&bytes, LabeledRecord).unwrap(), labeled_record);
+}
+
+#[test]
+fn record_decodes_missing_opt_label_ids_as_none() {
+    let bytes = IDLArgs::new(&[IDLValue::Record(vec![
+        IDLField {
+            id: Label::Id(0),
+            val: IDLValue::Nat(Nat::from(7u32)),
+        },
+        IDLField {
+            id: Label::Id(42),
+            val: IDLValue::Text("forty-two".to_string()),
+        },
+        IDLField {
+            id: Label::Id(label_hash("name")),
+            val: IDLValue::Text("name".to_string()),
+        },
+    ])])
+    .to_bytes()
+    .unwrap();
+
+    assert_eq!(*Decode!(&bytes, LabeledRecord).unwrap()._100_, None);
+}
+
+#[test]
+fn variant_round_trips_with_numeric_and_sparse_label_ids() {
+    let cases = vec![
+        (LabeledVariant::_1_(()), Label::Id(1), IDLValue::Null),
+        (
+            LabeledVariant::_7_("seven".to_string()),
+            Label::Id(7),
+            IDLValue::Text("seven".to_string()),
+        ),
+        (
+            LabeledVariant::other(Nat::from(3u32)),
+            Label::Id(label_hash("other")),
+            IDLValue::Nat(Nat::from(3u32)),
+        ),
+    ];
+
+    for (labeled_variant, id, val) in cases {
+        let bytes = Encode!(&labeled_variant).unwrap();
+        let args = IDLArgs::from_bytes(&bytes).unwrap();
+
+        match &args.args[..] {
+            [IDLValue::Variant(variant)] => {
+                assert_eq!(variant.0.id, id);
+                assert_eq!(variant.0.val, val);
+            }
+            args => panic!("expected a variant, got {:?}", args),
+        }
+        assert_eq!(Decode!(&bytes, LabeledVariant).unwrap(), labeled_variant);
+    }
+}
+
+/// Drops the whitespace and trailing commas that rustfmt changes in the
+/// expected impls
+fn normalize(token_stream: &TokenStream) -> String {
+    let mut normalized: String = token_stream
+        .to_string()
+        .chars()
+        .filter(|c| !c.is_whitespace())
+        .collect();
+
+    for delimiter in ["}", ")", "]"] {
+        normalized = normalized.replace(&format!(",{delimiter}"), delimiter);
+    }
+
+    normalized
+}
+
+fn label_hash(name: &str) -> u32 {
+    cdk_framework::label::hash_label(name)
+}